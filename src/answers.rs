@@ -0,0 +1,214 @@
+use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter};
+use std::path::Path;
+use advent_code_lib::all_lines;
+use anyhow::{anyhow, bail};
+use crate::runner::{Variant, VARIANTS};
+
+pub const ANSWERS_FILE: &'static str = "answers.toml";
+const PARTS: [usize; 2] = [1, 2];
+
+// Recorded answers live in a small TOML subset:
+//
+// [day12."ex/day12a.txt"]
+// part1 = "10"
+// part2 = "36"
+//
+// Every variant of a day is checked against that day's answers.
+// A part that has no answer yet records the error it reports instead:
+//
+// [day25."in/day25.txt"]
+// part2_error = "Day 25 has no part 2"
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Expected {
+    Answer(String),
+    Error(String)
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct Answers {
+    expected: BTreeMap<(usize, String, usize), Expected>
+}
+
+impl Answers {
+    pub fn from_file(filename: &str) -> anyhow::Result<Self> {
+        let mut answers = Answers::default();
+        let mut section = None;
+        for (i, line) in all_lines(filename)?.enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let located = |e: anyhow::Error| anyhow!("{}:{}: {}", filename, i + 1, e);
+            if line.starts_with('[') {
+                section = Some(parse_header(line).map_err(located)?);
+            } else {
+                let (day, input) = section.clone()
+                    .ok_or(anyhow!("answer outside of a [dayN.\"file\"] section")).map_err(located)?;
+                let (part, answer) = parse_answer(line).map_err(located)?;
+                answers.expected.insert((day, input, part), answer);
+            }
+        }
+        Ok(answers)
+    }
+
+    pub fn expected(&self, day: usize, input: &str, part: usize) -> Option<&Expected> {
+        self.expected.get(&(day, input.to_string(), part))
+    }
+
+    pub fn recorded_for(&self, day: usize) -> impl Iterator<Item=(&str, usize, &Expected)> + '_ {
+        self.expected.iter()
+            .filter(move |((d, _, _), _)| *d == day)
+            .map(|((_, input, part), expected)| (input.as_str(), *part, expected))
+    }
+}
+
+fn parse_header(line: &str) -> anyhow::Result<(usize, String)> {
+    let inner = line.strip_prefix('[').and_then(|s| s.strip_suffix(']'))
+        .ok_or(anyhow!("expected ']' at end of section header"))?;
+    let (day, input) = inner.split_once('.').ok_or(anyhow!("expected [dayN.\"file\"]"))?;
+    let day = day.trim().strip_prefix("day").ok_or(anyhow!("expected 'day' in section header"))?;
+    Ok((day.parse()?, unquote(input.trim())?))
+}
+
+fn parse_answer(line: &str) -> anyhow::Result<(usize, Expected)> {
+    let (key, value) = line.split_once('=').ok_or(anyhow!("expected partN = \"answer\""))?;
+    let part = key.trim().strip_prefix("part").ok_or(anyhow!("expected 'part1' or 'part2'"))?;
+    let value = unquote(value.trim())?;
+    Ok(match part.strip_suffix("_error") {
+        Some(part) => (part.parse()?, Expected::Error(value)),
+        None => (part.parse()?, Expected::Answer(value))
+    })
+}
+
+fn unquote(s: &str) -> anyhow::Result<String> {
+    let inner = s.strip_prefix('"').and_then(|s| s.strip_suffix('"'))
+        .ok_or(anyhow!("expected a quoted string, got {}", s))?;
+    let mut result = String::new();
+    let mut chars = inner.chars();
+    while let Some(c) = chars.next() {
+        if c == '\\' {
+            match chars.next() {
+                Some('n') => result.push('\n'),
+                Some('"') => result.push('"'),
+                Some('\\') => result.push('\\'),
+                other => bail!("unrecognized escape: \\{}", other.map_or(String::new(), |c| c.to_string()))
+            }
+        } else {
+            result.push(c);
+        }
+    }
+    Ok(result)
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Outcome {
+    Pass,
+    Fail {expected: String, actual: String},
+    Error(String),
+    Missing
+}
+
+#[derive(Clone, Debug)]
+pub struct Check {
+    pub variant: &'static str,
+    pub input: String,
+    pub part: usize,
+    pub outcome: Outcome
+}
+
+impl Check {
+    fn run(variant: &Variant, input: &str, part: usize, expected: &Expected) -> Self {
+        let outcome = match (variant.run(input, part), expected) {
+            (Ok(actual), Expected::Answer(answer)) if actual == *answer => Outcome::Pass,
+            (Ok(actual), Expected::Answer(answer)) => Outcome::Fail {expected: answer.clone(), actual},
+            (Ok(actual), Expected::Error(error)) => Outcome::Fail {expected: format!("error: {}", error), actual},
+            (Err(e), Expected::Error(error)) if e.to_string() == *error => Outcome::Pass,
+            (Err(e), _) => Outcome::Error(e.to_string())
+        };
+        Check {variant: variant.name, input: input.to_string(), part, outcome}
+    }
+}
+
+impl Display for Check {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} part {} on {}: ", self.variant, self.part, self.input)?;
+        match &self.outcome {
+            Outcome::Pass => write!(f, "pass"),
+            Outcome::Fail {expected, actual} => write!(f, "FAIL (expected {:?}, got {:?})", expected, actual),
+            Outcome::Error(e) => write!(f, "ERROR ({})", e),
+            Outcome::Missing => write!(f, "MISSING (no answer recorded)")
+        }
+    }
+}
+
+/// Runs every recorded answer for the selected days, and flags puzzle inputs with no recorded answer.
+pub fn verify<F: Fn(&Variant, &str) -> bool>(answers: &Answers, selected: F) -> Vec<Check> {
+    let mut checks = Vec::new();
+    for variant in VARIANTS.iter() {
+        for (input, part, expected) in answers.recorded_for(variant.day) {
+            if selected(variant, input) {
+                checks.push(Check::run(variant, input, part, expected));
+            }
+        }
+        let puzzle = format!("in/day{}.txt", variant.day);
+        if Path::new(puzzle.as_str()).exists() && selected(variant, puzzle.as_str()) {
+            for part in PARTS {
+                if answers.expected(variant.day, puzzle.as_str(), part).is_none() {
+                    checks.push(Check {variant: variant.name, input: puzzle.clone(), part, outcome: Outcome::Missing});
+                }
+            }
+        }
+    }
+    checks
+}
+
+#[cfg(test)]
+pub fn assert_examples(day: usize) {
+    let answers = Answers::from_file(ANSWERS_FILE).unwrap();
+    let checks = verify(&answers, |v, input| v.day == day && input.starts_with("ex/"));
+    assert!(checks.len() > 0);
+    for check in checks {
+        println!("{}", check);
+        assert_eq!(check.outcome, Outcome::Pass);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unquote() {
+        for (quoted, unquoted) in [("\"1588\"", "1588"), ("\"#.#\\n.#.\"", "#.#\n.#."), ("\"a\\\"b\"", "a\"b")] {
+            assert_eq!(unquote(quoted).unwrap(), unquoted);
+        }
+        assert!(unquote("1588").is_err());
+    }
+
+    #[test]
+    fn test_answer() {
+        assert_eq!(parse_answer("part1 = \"1588\"").unwrap(), (1, Expected::Answer("1588".to_string())));
+        assert_eq!(parse_answer("part2_error = \"Day 25 has no part 2\"").unwrap(),
+                   (2, Expected::Error("Day 25 has no part 2".to_string())));
+        assert!(parse_answer("part_error = \"oops\"").is_err());
+    }
+
+    #[test]
+    fn test_puzzle_inputs_recorded() {
+        let answers = Answers::from_file(ANSWERS_FILE).unwrap();
+        for variant in VARIANTS.iter() {
+            let puzzle = variant.default_input(false).unwrap();
+            for part in PARTS {
+                assert!(answers.expected(variant.day, puzzle.as_str(), part).is_some(), "{} part {}", puzzle, part);
+            }
+        }
+    }
+
+    #[test]
+    fn test_header() {
+        assert_eq!(parse_header("[day12.\"ex/day12b.txt\"]").unwrap(), (12, "ex/day12b.txt".to_string()));
+        assert!(parse_header("[twelve.\"ex/day12b.txt\"]").is_err());
+    }
+}
@@ -0,0 +1,218 @@
+use std::fmt::{Display, Formatter};
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::Path;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use advent_code_lib::all_lines;
+use anyhow::anyhow;
+use serde::{Deserialize, Serialize};
+use crate::Solver;
+use crate::runner::Variant;
+
+pub const HISTORY_FILE: &'static str = "bench_history.jsonl";
+pub const DEFAULT_RUNS: usize = 5;
+pub const DEFAULT_THRESHOLD: f64 = 0.2;
+
+/// Wall times for one run. A part that returns an error (e.g. not solved yet) has no time.
+#[derive(Copy, Clone, Debug)]
+pub struct Sample {
+    pub parse: Duration,
+    pub part1: Option<Duration>,
+    pub part2: Option<Duration>
+}
+
+pub fn time_parts<S: Solver>(filename: &str) -> anyhow::Result<Sample> {
+    let start = Instant::now();
+    let input = S::parse(filename)?;
+    let parse = start.elapsed();
+    let start = Instant::now();
+    let part1 = S::part1(&input).ok().map(|_| start.elapsed());
+    let start = Instant::now();
+    let part2 = S::part2(&input).ok().map(|_| start.elapsed());
+    Ok(Sample {parse, part1, part2})
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct Stats {
+    pub min: Duration,
+    pub median: Duration,
+    pub max: Duration
+}
+
+impl Stats {
+    pub fn from(times: &Vec<Duration>) -> Option<Self> {
+        let mut times = times.clone();
+        times.sort();
+        Some(Stats {min: *times.first()?, median: times[times.len() / 2], max: *times.last()?})
+    }
+}
+
+impl Display for Stats {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "min {:?}, median {:?}, max {:?}", self.min, self.median, self.max)
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct BenchResult {
+    pub variant: &'static str,
+    pub input: String,
+    pub runs: usize,
+    pub parse: Stats,
+    pub part1: Option<Stats>,
+    pub part2: Option<Stats>
+}
+
+impl BenchResult {
+    pub fn phases(&self) -> [(&'static str, Option<Stats>); 3] {
+        [("parse", Some(self.parse)), ("part1", self.part1), ("part2", self.part2)]
+    }
+}
+
+impl Display for BenchResult {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} on {} ({} runs)", self.variant, self.input, self.runs)?;
+        for (phase, stats) in self.phases() {
+            match stats {
+                Some(stats) => write!(f, "\n  {}: {}", phase, stats)?,
+                None => write!(f, "\n  {}: unsolved", phase)?
+            }
+        }
+        Ok(())
+    }
+}
+
+pub fn bench(variant: &Variant, input: &str, runs: usize) -> anyhow::Result<BenchResult> {
+    let mut samples = Vec::new();
+    for _ in 0..runs {
+        samples.push(variant.time(input)?);
+    }
+    // A part only counts as solved if it succeeded on every run.
+    let stats = |phase: fn(&Sample) -> Option<Duration>| samples.iter()
+        .map(phase)
+        .collect::<Option<Vec<_>>>()
+        .and_then(|times| Stats::from(&times));
+    Ok(BenchResult {
+        variant: variant.name, input: input.to_string(), runs,
+        parse: stats(|s| Some(s.parse)).ok_or(anyhow!("{} needs at least one run", variant.name))?,
+        part1: stats(|s| s.part1),
+        part2: stats(|s| s.part2)
+    })
+}
+
+/// One line of the history file; times are medians in microseconds.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct HistoryEntry {
+    pub timestamp: u64,
+    pub variant: String,
+    pub input: String,
+    pub runs: usize,
+    pub parse_us: u128,
+    pub part1_us: Option<u128>,
+    pub part2_us: Option<u128>
+}
+
+impl HistoryEntry {
+    pub fn from(result: &BenchResult) -> Self {
+        HistoryEntry {
+            timestamp: SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs()),
+            variant: result.variant.to_string(),
+            input: result.input.clone(),
+            runs: result.runs,
+            parse_us: result.parse.median.as_micros(),
+            part1_us: result.part1.map(|s| s.median.as_micros()),
+            part2_us: result.part2.map(|s| s.median.as_micros())
+        }
+    }
+
+    fn phases(&self) -> [(&'static str, Option<u128>); 3] {
+        [("parse", Some(self.parse_us)), ("part1", self.part1_us), ("part2", self.part2_us)]
+    }
+
+    /// Phases that took more than `threshold` (e.g. 0.2 for 20%) longer than in `previous`.
+    pub fn slowdowns(&self, previous: &HistoryEntry, threshold: f64) -> Vec<Slowdown> {
+        self.phases().iter().zip(previous.phases().iter())
+            .filter_map(|((phase, now), (_, before))| match (now, before) {
+                (Some(now), Some(before)) if *now as f64 > *before as f64 * (1.0 + threshold) =>
+                    Some(Slowdown {phase, before_us: *before, now_us: *now}),
+                _ => None
+            })
+            .collect()
+    }
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct Slowdown {
+    pub phase: &'static str,
+    pub before_us: u128,
+    pub now_us: u128
+}
+
+impl Display for Slowdown {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let percent = 100.0 * (self.now_us as f64 / self.before_us.max(1) as f64 - 1.0);
+        write!(f, "SLOWER {}: median {}us, was {}us (+{:.0}%)", self.phase, self.now_us, self.before_us, percent)
+    }
+}
+
+pub fn read_history(filename: &str) -> anyhow::Result<Vec<HistoryEntry>> {
+    if !Path::new(filename).exists() {
+        return Ok(Vec::new());
+    }
+    let mut entries = Vec::new();
+    for (i, line) in all_lines(filename)?.enumerate() {
+        if !line.trim().is_empty() {
+            entries.push(serde_json::from_str(line.as_str())
+                .map_err(|e| anyhow!("{}:{}: {}", filename, i + 1, e))?);
+        }
+    }
+    Ok(entries)
+}
+
+pub fn append_history(filename: &str, entry: &HistoryEntry) -> anyhow::Result<()> {
+    let mut file = OpenOptions::new().create(true).append(true).open(filename)?;
+    writeln!(file, "{}", serde_json::to_string(entry)?)?;
+    Ok(())
+}
+
+pub fn latest_for<'a>(history: &'a Vec<HistoryEntry>, variant: &str, input: &str) -> Option<&'a HistoryEntry> {
+    history.iter().rev().find(|e| e.variant == variant && e.input == input)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(parse_us: u128, part1_us: Option<u128>, part2_us: Option<u128>) -> HistoryEntry {
+        HistoryEntry {timestamp: 0, variant: "day15".to_string(), input: "in/day15.txt".to_string(), runs: 5,
+            parse_us, part1_us, part2_us}
+    }
+
+    #[test]
+    fn test_stats() {
+        let times = [7, 1, 3, 9, 4].iter().map(|ms| Duration::from_millis(*ms)).collect();
+        let stats = Stats::from(&times).unwrap();
+        assert_eq!(stats.min, Duration::from_millis(1));
+        assert_eq!(stats.median, Duration::from_millis(4));
+        assert_eq!(stats.max, Duration::from_millis(9));
+        assert_eq!(Stats::from(&Vec::new()), None);
+    }
+
+    #[test]
+    fn test_slowdowns() {
+        let before = entry(100, Some(1000), None);
+        let now = entry(110, Some(1500), Some(2000));
+        let slowdowns = now.slowdowns(&before, DEFAULT_THRESHOLD);
+        assert_eq!(slowdowns, vec![Slowdown {phase: "part1", before_us: 1000, now_us: 1500}]);
+        assert_eq!(format!("{}", slowdowns[0]), "SLOWER part1: median 1500us, was 1000us (+50%)");
+        assert!(now.slowdowns(&before, 1.0).is_empty());
+    }
+
+    #[test]
+    fn test_history_line() {
+        let original = entry(100, Some(1000), None);
+        let line = serde_json::to_string(&original).unwrap();
+        assert!(!line.contains('\n'));
+        assert_eq!(serde_json::from_str::<HistoryEntry>(line.as_str()).unwrap(), original);
+    }
+}
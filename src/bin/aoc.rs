@@ -0,0 +1,178 @@
+use std::env;
+use anyhow::{anyhow, bail};
+use advent2021::answers::{Answers, ANSWERS_FILE, Outcome, verify};
+use advent2021::bench::{append_history, bench, DEFAULT_RUNS, DEFAULT_THRESHOLD, HistoryEntry, HISTORY_FILE, latest_for, read_history};
+use advent2021::differential::{DEFAULT_SEEDS, PAIRS};
+use advent2021::generator::{DEFAULT_SEED, find_generator, GENERATORS};
+use advent2021::runner::{find_variant, Variant, VARIANTS};
+
+const USAGE: &'static str = "Usage: aoc list
+       aoc verify [--day N] [--answers FILE]
+       aoc bench [--day N [--variant NAME]] [--runs N] [--example] [--history FILE] [--threshold PERCENT]
+       aoc gen --day N [--size N] [--seed N]
+       aoc diff [--day N] [--seeds N]
+       aoc --day N [--part (1|2)] [--variant NAME] [--input FILE|- | --example] [--format (text|json)]";
+
+fn main() -> anyhow::Result<()> {
+    let args: Vec<String> = env::args().skip(1).collect();
+    match args.first().map(|a| a.as_str()) {
+        Some("list") => {
+            list();
+            Ok(())
+        }
+        Some("verify") => verify_answers(&args),
+        Some("bench") => bench_variants(&args),
+        Some("gen") => generate_input(&args),
+        Some("diff") => compare_pairs(&args),
+        _ => run(&args)
+    }
+}
+
+fn list() {
+    for variant in VARIANTS.iter() {
+        let tag = if variant.is_default() {""} else {" (variant)"};
+        println!("{:>2} {}{} [{}]", variant.day, variant.name, tag, variant.default_input(false).unwrap_or_else(|e| e.to_string()));
+    }
+}
+
+fn verify_answers(args: &Vec<String>) -> anyhow::Result<()> {
+    let answers = Answers::from_file(flag_value(args, "--answers").unwrap_or(ANSWERS_FILE))?;
+    let day = flag_value(args, "--day").map(|d| d.parse::<usize>()).transpose()?;
+    let checks = verify(&answers, |v, _| day.map_or(true, |d| v.day == d));
+    for check in checks.iter() {
+        println!("{}", check);
+    }
+    let count = |f: fn(&Outcome) -> bool| checks.iter().filter(|c| f(&c.outcome)).count();
+    let passed = count(|o| *o == Outcome::Pass);
+    let missing = count(|o| *o == Outcome::Missing);
+    let failed = checks.len() - passed - missing;
+    println!("{} passed, {} failed, {} missing", passed, failed, missing);
+    if failed > 0 {
+        bail!("{} recorded answers did not match", failed);
+    }
+    if missing > 0 {
+        bail!("{} puzzle answers are not recorded in {}", missing, flag_value(args, "--answers").unwrap_or(ANSWERS_FILE));
+    }
+    Ok(())
+}
+
+fn bench_variants(args: &Vec<String>) -> anyhow::Result<()> {
+    let runs = flag_value(args, "--runs").map_or(Ok(DEFAULT_RUNS), |r| r.parse::<usize>())?;
+    let threshold = flag_value(args, "--threshold").map_or(Ok(DEFAULT_THRESHOLD), |t| t.parse::<f64>().map(|t| t / 100.0))?;
+    let history_file = flag_value(args, "--history").unwrap_or(HISTORY_FILE);
+    let history = read_history(history_file)?;
+    let example = args.contains(&"--example".to_string());
+    let variants = match flag_value(args, "--day") {
+        None => VARIANTS.to_vec(),
+        Some(day) => vec![find_variant(day.parse::<usize>()?, flag_value(args, "--variant"))?]
+    };
+    for variant in variants {
+        let input = match variant.default_input(example) {
+            Ok(input) => input,
+            Err(e) => {
+                println!("{}: ERROR ({})", variant.name, e);
+                continue;
+            }
+        };
+        match bench(&variant, input.as_str(), runs) {
+            Ok(result) => {
+                println!("{}", result);
+                let entry = HistoryEntry::from(&result);
+                if let Some(previous) = latest_for(&history, variant.name, input.as_str()) {
+                    for slowdown in entry.slowdowns(previous, threshold) {
+                        println!("  {}", slowdown);
+                    }
+                }
+                append_history(history_file, &entry)?;
+            }
+            Err(e) => println!("{} on {}: ERROR ({})", variant.name, input, e)
+        }
+    }
+    Ok(())
+}
+
+/// Prints the input to stdout, so it can be piped into `aoc --day N --input -`.
+/// Answers known by construction go to stderr.
+fn generate_input(args: &Vec<String>) -> anyhow::Result<()> {
+    let day = flag_value(args, "--day")
+        .ok_or(anyhow!("aoc gen --day N [--size N] [--seed N]\nSizes: {}",
+            GENERATORS.iter().map(|g| format!("day {}: {} ({})", g.day, g.size_of, g.default_size)).collect::<Vec<_>>().join(", ")))?
+        .parse::<usize>()?;
+    let generator = find_generator(day)?;
+    let size = flag_value(args, "--size").map_or(Ok(generator.default_size), |s| s.parse::<usize>())?;
+    let seed = flag_value(args, "--seed").map_or(Ok(DEFAULT_SEED), |s| s.parse::<u64>())?;
+    let generated = generator.generate(seed, size);
+    print!("{}", generated.input);
+    for (part, expected) in generated.expected.iter().enumerate() {
+        if let Some(expected) = expected {
+            eprintln!("Day {} part {} expected: {}", day, part + 1, expected);
+        }
+    }
+    Ok(())
+}
+
+fn compare_pairs(args: &Vec<String>) -> anyhow::Result<()> {
+    let day = flag_value(args, "--day").map(|d| d.parse::<usize>()).transpose()?;
+    let seeds = flag_value(args, "--seeds").map_or(Ok(DEFAULT_SEEDS), |s| s.parse::<u64>())?;
+    let mut diverged = 0;
+    for pair in PAIRS.iter().filter(|p| day.map_or(true, |d| p.day == d)) {
+        let mut inputs = pair.recorded_inputs("ex")?;
+        inputs.append(&mut pair.recorded_inputs("in")?);
+        let count = inputs.len() + seeds as usize;
+        match pair.first_divergence(inputs.into_iter().chain(pair.generated_inputs(seeds))) {
+            None => println!("{} and {} agree on {} inputs", pair.names[0], pair.names[1], count),
+            Some(divergence) => {
+                println!("{}", divergence);
+                diverged += 1;
+            }
+        }
+    }
+    if diverged > 0 {
+        bail!("{} pairs of implementations disagree", diverged);
+    }
+    Ok(())
+}
+
+fn run(args: &Vec<String>) -> anyhow::Result<()> {
+    let day = flag_value(args, "--day")
+        .ok_or(anyhow!("{}", USAGE))?
+        .parse::<usize>()?;
+    let variant = find_variant(day, flag_value(args, "--variant"))?;
+    let input = flag_value(args, "--input")
+        .map(|s| Ok(s.to_string()))
+        .unwrap_or_else(|| variant.default_input(args.contains(&"--example".to_string())))?;
+    let parts = match flag_value(args, "--part") {
+        None => vec![1, 2],
+        Some(part) => vec![part.parse::<usize>()?]
+    };
+    let json = match flag_value(args, "--format") {
+        None | Some("text") => false,
+        Some("json") => true,
+        Some(other) => bail!("Unknown format {}; expected text or json", other)
+    };
+    for part in parts {
+        report(&variant, input.as_str(), part, json)?;
+    }
+    Ok(())
+}
+
+fn report(variant: &Variant, input: &str, part: usize, json: bool) -> anyhow::Result<()> {
+    match variant.answer(input, part) {
+        Ok(answer) => {
+            if json {
+                println!("{}", serde_json::to_string(&answer)?);
+            } else {
+                println!("Day {} part {}: {}", answer.day, answer.part, answer.answer);
+            }
+            Ok(())
+        }
+        Err(e) => bail!("{} part {} on {}: {}", variant.name, part, input, e)
+    }
+}
+
+fn flag_value<'a>(args: &'a Vec<String>, flag: &str) -> Option<&'a str> {
+    args.iter()
+        .position(|a| a == flag)
+        .and_then(|i| args.get(i + 1))
+        .map(|s| s.as_str())
+}
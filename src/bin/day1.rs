@@ -1,21 +1,17 @@
 use std::io;
-use advent_code_lib::{advent_main, file2nums};
+use advent_code_lib::advent_main;
+use advent2021::day1::{Aggregate, analyze};
 
 fn main() -> io::Result<()> {
-    advent_main(&["window_width"], &[], |args| {
-        let depths = file2nums(format!("{}", args[1]).as_str())?;
-        let mut count = 0;
+    advent_main(&["window_width"], &["-aggregate:(sum|min|max|mean)"], |args| {
         let window_width = args[2].parse::<usize>().unwrap();
-        for i in 0..depths.len() - window_width {
-            if window_total(&depths, i, window_width) < window_total(&depths, i + 1, window_width) {
-                count += 1;
-            }
-        }
-        println!("{}", count);
+        let aggregate = match args.iter().find(|arg| arg.starts_with("-aggregate")) {
+            None => Aggregate::Sum,
+            Some(arg) => arg.split(':').nth(1).unwrap_or("").parse::<Aggregate>()?
+        };
+        let trends = analyze(advent2021::input_lines(args[1].as_str())?, window_width, aggregate)?;
+        println!("{}", trends.increases);
+        println!("{}", trends);
         Ok(())
     })
 }
-
-fn window_total(depths: &Vec<isize>, i: usize, window_width: usize) -> isize {
-    (i..i+window_width).map(|j| depths[j]).sum()
-}
\ No newline at end of file
@@ -1,16 +1,34 @@
-use std::collections::VecDeque;
-use std::fmt::{Display, Formatter};
 use std::io;
-use advent_code_lib::{advent_main, Position, ContinueSearch, GridDigitWorld, search};
-use bare_metal_modulo::*;
+use advent_code_lib::{advent_main, make_inner_io_error, make_io_error};
+use advent2021::day11::{DumboOctopi, part_1, part_2, show_steps};
+use advent2021::grid::Neighbourhood;
 
 fn main() -> io::Result<()> {
-    advent_main(&[], &["-show:num_steps"], |args| {
-        let octopi = DumboOctopi::new(args[1].as_str())?;
+    advent_main(&[], &["-show:num_steps", "-threshold:energy", "-neighbourhood:(4|8|hex)", "-torus", "-cycle"], |args| {
+        let mut octopi = DumboOctopi::new(args[1].as_str())
+            .map_err(|e| make_inner_io_error(e.to_string().as_str()))?;
+        for arg in args.iter() {
+            if let Some(threshold) = arg.strip_prefix("-threshold:") {
+                match threshold.parse() {
+                    Ok(threshold) => octopi = octopi.with_threshold(threshold),
+                    Err(_) => return make_io_error(format!("Not an energy: {}", threshold).as_str())
+                }
+            } else if let Some(neighbourhood) = arg.strip_prefix("-neighbourhood:") {
+                octopi = octopi.with_neighbourhood(neighbourhood.parse::<Neighbourhood>()?)
+                    .map_err(|e| make_inner_io_error(e.to_string().as_str()))?;
+            } else if arg == "-torus" {
+                octopi = octopi.with_wrap_around(true)
+                    .map_err(|e| make_inner_io_error(e.to_string().as_str()))?;
+            }
+        }
         match args.iter().find(|arg| arg.starts_with("-show")) {
             None => {
                 println!("Part 1 score: {}", part_1(octopi.clone()));
-                println!("Part 2 score: {}", part_2(octopi));
+                println!("Part 2 score: {}", part_2(octopi.clone()));
+                if args.iter().any(|arg| arg == "-cycle") {
+                    let cycle = octopi.flash_cycle();
+                    println!("Cycle of length {} after {} steps", cycle.period, cycle.pre_period);
+                }
             }
             Some(show_step) => {
                 let steps: usize = show_step.split(':').nth(1).unwrap().parse().unwrap();
@@ -20,89 +38,3 @@ fn main() -> io::Result<()> {
         Ok(())
     })
 }
-
-const PART_1_ITERATIONS: usize = 100;
-
-fn part_1(octopi: DumboOctopi) -> usize {
-    octopi.take(PART_1_ITERATIONS).sum()
-}
-
-fn part_2(octopi: DumboOctopi) -> usize {
-    let target_flashes = octopi.len();
-    1 + octopi.enumerate().find(|(_, flashes)| *flashes == target_flashes)
-        .map(|(step, _)| step).unwrap()
-}
-
-fn show_steps(mut octopi: DumboOctopi, steps: usize) {
-    println!("Before any steps:");
-    println!("{}", octopi);
-    println!();
-
-    for step in 1..=steps {
-        let flashes = octopi.next().unwrap();
-        println!("After step {} ({} flashes):", step, flashes);
-        println!("{}", octopi);
-        println!();
-    }
-}
-
-#[derive(Clone, Debug)]
-struct DumboOctopi {
-    energies: GridDigitWorld,
-}
-
-impl DumboOctopi {
-    fn new(filename: &str) -> io::Result<DumboOctopi> {
-        let energies = GridDigitWorld::from_digit_file(filename).unwrap();
-        Ok(DumboOctopi {energies})
-    }
-
-    fn just_flashed(&self) -> impl Iterator<Item=Position> + '_ {
-        self.energies.position_value_iter()
-            .filter(|(_, energy)| **energy == 0)
-            .map(|(p, _)| *p)
-    }
-
-    fn enqueue_flashed_neighbors(&mut self, flasher: Position, queue: &mut VecDeque<Position>) {
-        for neighbor in flasher.neighbors() {
-            self.energies.modify(neighbor, |neighbor_energy| {
-                if *neighbor_energy > 0 {
-                    *neighbor_energy += 1;
-                    if *neighbor_energy == 0 {
-                        queue.push_back(neighbor);
-                    }
-                }
-            });
-        }
-    }
-
-    fn len(&self) -> usize {
-        self.energies.len()
-    }
-}
-
-impl Iterator for DumboOctopi {
-    type Item = usize;
-
-    fn next(&mut self) -> Option<Self::Item> {
-        for (_, energy) in self.energies.position_value_iter_mut() {
-            *energy += 1;
-        }
-
-        let result = search(self.just_flashed().collect(),
-                            |flasher, q| {
-                                self.enqueue_flashed_neighbors(*flasher, q);
-                                ContinueSearch::Yes});
-        Some(*result.dequeued())
-    }
-}
-
-impl Display for DumboOctopi {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        for p in self.energies.position_iter() {
-            if p.col == 0 && p.row > 0 {writeln!(f)?;}
-            write!(f, "{}", self.energies.value(p).unwrap().a())?
-        }
-        Ok(())
-    }
-}
\ No newline at end of file
@@ -1,14 +1,7 @@
 use std::io;
-use advent_code_lib::{AdjacencySets, all_lines, Arena, advent_main, ParentMapQueue, search, SearchQueue, ContinueSearch};
-use hash_histogram::HashHistogram;
+use advent_code_lib::advent_main;
+use advent2021::day12_dfs::{all_paths, build_graph_from, Rule, show};
 
-// NOTE:
-// * No big cave is ever connected to another big cave!
-// * If 2 big caves were connected, you could bounce between them indefinitely, leading
-//   to an infinite number of paths.
-
-const START: &'static str = "start";
-const END: &'static str = "end";
 const SHOW_PATH_ARG: &'static str = "-show-paths";
 
 fn main() -> io::Result<()> {
@@ -21,79 +14,3 @@ fn main() -> io::Result<()> {
         Ok(())
     })
 }
-
-pub fn build_graph_from(filename: &str) -> io::Result<AdjacencySets> {
-    let mut graph = AdjacencySets::new();
-    for line in all_lines(filename)? {
-        let parts: Vec<&str> = line.split('-').collect();
-        graph.connect2(parts[0], parts[1]);
-    }
-    Ok(graph)
-}
-
-pub fn has_upper(s: &str) -> bool {
-    s.chars().any(|c| c.is_uppercase())
-}
-
-fn show(paths: &Vec<Vec<String>>) {
-    for path in paths.iter() {
-        println!("{:?}", path);
-    }
-}
-
-fn all_paths(graph: &AdjacencySets, rule: Rule) -> Vec<Vec<String>> {
-    let mut all_paths = Vec::new();
-    let mut arena = Arena::new();
-    let mut stack: ParentMapQueue<usize, Vec<usize>> = ParentMapQueue::new();
-    stack.enqueue(&(arena.alloc(START.to_string(), None)));
-    search(stack, |node, stack| {
-        let parent = stack.parent_of(node);
-        let last_name = arena.get(*node).get().as_str();
-        if rule.allows(&arena, last_name, *parent) {
-            if last_name == END {
-                all_paths.push(path_at_addr(&arena, *node));
-            } else {
-                for neighbor in graph.neighbors_of(last_name).unwrap() {
-                    let new_addr = arena.alloc(neighbor.clone(), Some(*node));
-                    stack.enqueue(&new_addr);
-                }
-            }
-        }
-        ContinueSearch::Yes
-    });
-    all_paths
-}
-
-fn path_at_addr(arena: &Arena<String>, addr: usize) -> Vec<String> {
-    let mut path = arena.iter_from(addr).cloned().collect::<Vec<_>>();
-    path.reverse();
-    path
-}
-
-#[derive(Copy, Clone, Debug)]
-enum Rule {
-    Part1, Part2
-}
-
-impl Rule {
-    fn allows(&self, arena: &Arena<String>, node: &str, parent: Option<usize>) -> bool {
-        match parent {
-            None => true,
-            Some(parent_addr) => {
-                let mut path_counts: HashHistogram<String> = arena.iter_from(parent_addr).collect();
-                path_counts.bump(&node.to_string());
-                let mut potential_problems = path_counts.iter()
-                    .filter(|(s, c)| !has_upper(s.as_str()) && **c > 1);
-                match self {
-                    Rule::Part1 => potential_problems.next().is_none(),
-                    Rule::Part2 => {
-                        let problems = potential_problems.collect::<Vec<_>>();
-                        problems.iter().find(|(s, _)| [START, END].contains(&s.as_str())).is_none()
-                            && !problems.iter().any(|(_, count)| **count > 2)
-                            && problems.len() <= 1
-                    }
-                }
-            }
-        }
-    }
-}
\ No newline at end of file
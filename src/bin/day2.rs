@@ -1,55 +1,38 @@
+use std::fs::File;
 use std::io;
-use advent_code_lib::{for_each_line, advent_main, Position};
+use advent_code_lib::advent_main;
+use advent2021::day2::{Course, parse_commands, Rule, Submarine};
 
 fn main() -> io::Result<()> {
-    advent_main(&["(1|2)"], &[], |args| {
-        let mut sub = Submarine::new();
-        for_each_line(args[1].as_str(), |line| {
-            let parts: Vec<&str> = line.split_whitespace().collect();
-            let distance = parts[1].parse::<isize>().unwrap();
-            match args[2].as_str() {
-                "1" => sub.update_1(parts[0], distance),
-                "2" => sub.update_2(parts[0], distance),
-                other => {println!("Illegal argument: {}", other);}
+    advent_main(&["(1|2)"], &["-trace", "-compare", "-csv:filename", "-svg:filename"], |args| {
+        let script = parse_commands(args[1].as_str())?;
+        let rule = match args[2].as_str() {
+            "1" => Rule::Part1,
+            "2" => Rule::Part2,
+            other => {
+                println!("Illegal argument: {}", other);
+                return Ok(());
             }
-            Ok(())
-        })?;
-        sub.report();
+        };
+        if args.iter().any(|arg| arg == "-compare") {
+            for (part1, part2) in Submarine::trajectories(&script) {
+                println!("part 1: {}", part1);
+                println!("part 2: {}", part2);
+            }
+        } else if args.iter().any(|arg| arg == "-trace") {
+            for step in Submarine::trace(&script, rule) {
+                println!("{}", step);
+            }
+        }
+        let course = Course::of(&script);
+        for arg in args.iter() {
+            if let Some(filename) = arg.strip_prefix("-csv:") {
+                course.write_csv(&mut File::create(filename)?)?;
+            } else if let Some(filename) = arg.strip_prefix("-svg:") {
+                course.write_svg(&mut File::create(filename)?)?;
+            }
+        }
+        Submarine::after(&script, rule).report();
         Ok(())
     })
 }
-
-struct Submarine {
-    pos: Position,
-    aim: isize
-}
-
-impl Submarine {
-    pub fn new() -> Self {
-        Submarine {pos: Position::new(), aim: 0}
-    }
-
-    pub fn report(&self) {
-        println!("Final position: horizontal: {}, depth: {}, product: {}",
-                 self.pos.col, self.pos.row, self.pos.col * self.pos.row);
-    }
-
-    pub fn update_1(&mut self, command: &str, distance: isize) {
-        self.pos += match command {
-            "forward" => Position::from((distance, 0)),
-            "down" => Position::from((0, distance)),
-            "up" => Position::from((0, -distance)),
-            other => panic!("Illegible input: {}", other)
-        };
-    }
-
-    pub fn update_2(&mut self, command: &str, distance: isize) {
-        match command {
-            "forward" => self.pos += Position::from((distance, distance * self.aim)),
-            "down" => self.aim += distance,
-            "up" => self.aim -= distance,
-            other => panic!("Illegible input: {}", other)
-        };
-    }
-}
-
@@ -1,61 +1,42 @@
-use advent_code_lib::{breadth_first_search, Position, SearchQueue, ContinueSearch, GridDigitWorld, simpler_main};
-use bare_metal_modulo::{MNum, ModNumC};
-
-const MIN_SAFE_HEIGHT: u8 = 9;
-const NUM_LARGEST_BASINS: usize = 3;
-
-fn main() -> anyhow::Result<()> {
-    simpler_main(|filename| {
-        let heights = HeightMap::from(filename)?;
+use std::fs::File;
+use std::io;
+use advent_code_lib::{advent_main, make_io_error};
+use advent2021::day9::HeightMap;
+use advent2021::grid::Neighbourhood;
+
+fn main() -> io::Result<()> {
+    advent_main(&[], &["-basins", "-csv:filename", "-neighbourhood:(4|8|hex)", "-barrier:height", "-merge-tree"], |args| {
+        let mut heights = match HeightMap::from(args[1].as_str()) {
+            Ok(heights) => heights,
+            Err(e) => return make_io_error(e.to_string().as_str())
+        };
+        for arg in args.iter() {
+            if let Some(neighbourhood) = arg.strip_prefix("-neighbourhood:") {
+                heights = heights.with_neighbourhood(neighbourhood.parse::<Neighbourhood>()?);
+            } else if let Some(barrier) = arg.strip_prefix("-barrier:") {
+                match barrier.parse() {
+                    Ok(barrier) => heights = heights.with_barrier(barrier),
+                    Err(_) => return make_io_error(format!("Not a height: {}", barrier).as_str())
+                }
+            }
+        }
+        if args.iter().any(|arg| arg == "-merge-tree") {
+            let tree = heights.merge_tree();
+            for barrier in 0..=10 {
+                let mut sizes = tree.sizes(barrier);
+                sizes.sort_unstable_by(|a, b| b.cmp(a));
+                println!("barrier {:>2}: {} basins, sizes {:?}", barrier, tree.num_basins(barrier), sizes);
+            }
+        }
+        let basins = heights.basins();
+        if args.iter().any(|arg| arg == "-basins") {
+            println!("{}", basins);
+        }
+        if let Some(filename) = args.iter().find_map(|arg| arg.strip_prefix("-csv:")) {
+            basins.write_csv(&mut File::create(filename)?)?;
+        }
         println!("Part 1: {}", heights.risk_level_sum());
-        println!("Part 2: {}", heights.largest_basin_product());
+        println!("Part 2: {} basins, largest product {}", basins.num_basins(), basins.largest_basin_product(3));
         Ok(())
     })
 }
-
-struct HeightMap {
-    heights: GridDigitWorld,
-}
-
-impl HeightMap {
-    fn from(filename: &str) -> anyhow::Result<Self> {
-        Ok(HeightMap {heights: GridDigitWorld::from_digit_file(filename)?})
-    }
-
-    fn risk_level_sum(&self) -> u32 {
-        self.low_points().map(|(_, h)| h.a() as u32 + 1).sum()
-    }
-
-    fn largest_basin_product(&self) -> usize {
-        let mut basin_sizes: Vec<usize> = self.all_basin_sizes().collect();
-        basin_sizes.sort();
-        (1..=NUM_LARGEST_BASINS).map(|i| basin_sizes[basin_sizes.len() - i]).product()
-    }
-
-    fn low_points(&self) -> impl Iterator<Item=(Position,ModNumC<u8, 10>)> + '_ {
-        self.heights.position_value_iter()
-            .filter(|(p, h)| self.adjacent_location_heights(*p).all(|nh| nh > **h))
-            .map(|(p, h)| (*p, *h))
-    }
-
-    fn basin_size_for(&self, p: &Position) -> usize {
-        breadth_first_search(p,
-                             |c, q| {
-                                 for n in c.manhattan_neighbors()
-                                     .filter(|n| self.heights.value(*n)
-                                         .map_or(false, |h| h < MIN_SAFE_HEIGHT)) {
-                                     q.enqueue(&n);
-                                 }
-                                 ContinueSearch::Yes
-                             })
-            .len()
-    }
-
-    fn all_basin_sizes(&self) -> impl Iterator<Item=usize> + '_ {
-        self.low_points().map(|(low, _)| self.basin_size_for(&low))
-    }
-
-    fn adjacent_location_heights<'a>(&'a self, p: &'a Position) -> impl Iterator<Item=ModNumC<u8, 10>> + 'a {
-        p.manhattan_neighbors().filter_map(|n| self.heights.value(n))
-    }
-}
\ No newline at end of file
@@ -0,0 +1,219 @@
+use std::collections::VecDeque;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+use itertools::process_results;
+use crate::Solver;
+use crate::parse_error::{numbers_each, parse_number, ParseError, ParseResult};
+
+pub struct Day1;
+
+impl Solver for Day1 {
+    type Input = Vec<isize>;
+    type Part1 = usize;
+    type Part2 = usize;
+
+    fn parse_lines<I: Iterator<Item=String>>(lines: I) -> anyhow::Result<Self::Input> {
+        Ok(numbers_each(lines)?)
+    }
+
+    fn part1(input: &Self::Input) -> anyhow::Result<Self::Part1> {
+        Ok(count_increases(input, 1))
+    }
+
+    fn part2(input: &Self::Input) -> anyhow::Result<Self::Part2> {
+        Ok(count_increases(input, 3))
+    }
+}
+
+pub fn count_increases(depths: &Vec<isize>, window_width: usize) -> usize {
+    Trends::of(depths.iter().copied().sliding_windows(window_width).map(|w| w.sum)).increases
+}
+
+/// Trends in `aggregate` over windows of `window_width` depths, reading one line at a time.
+pub fn analyze<I: Iterator<Item=String>>(lines: I, window_width: usize, aggregate: Aggregate) -> ParseResult<Trends> {
+    let depths = lines.enumerate()
+        .map(|(i, line)| parse_number(line.as_str()).map_err(|e| e.at_line(i + 1)));
+    process_results(depths, |depths| {
+        Trends::of(depths.sliding_windows(window_width).map(|w| w.aggregate(aggregate)))
+    })
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Aggregate {Sum, Min, Max, Mean}
+
+impl FromStr for Aggregate {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "sum" => Ok(Aggregate::Sum),
+            "min" => Ok(Aggregate::Min),
+            "max" => Ok(Aggregate::Max),
+            "mean" => Ok(Aggregate::Mean),
+            other => Err(ParseError::expected("sum, min, max or mean", other))
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct Window {
+    pub width: usize,
+    pub sum: isize,
+    pub min: isize,
+    pub max: isize
+}
+
+impl Window {
+    pub fn mean(&self) -> f64 {
+        self.sum as f64 / self.width as f64
+    }
+
+    pub fn aggregate(&self, aggregate: Aggregate) -> f64 {
+        match aggregate {
+            Aggregate::Sum => self.sum as f64,
+            Aggregate::Min => self.min as f64,
+            Aggregate::Max => self.max as f64,
+            Aggregate::Mean => self.mean()
+        }
+    }
+}
+
+/// Yields a `Window` for every run of `width` consecutive depths, in amortized O(1) per depth.
+/// Only the current window is kept, so the depths may come from an unbounded stream.
+pub struct SlidingWindow<I> {
+    depths: I,
+    width: usize,
+    window: VecDeque<isize>,
+    sum: isize,
+    // Candidates for the window's min and max, kept in window order: a depth is dropped
+    // as soon as a later, smaller (resp. larger) depth enters, since it can never be the extreme again.
+    mins: VecDeque<isize>,
+    maxes: VecDeque<isize>
+}
+
+impl<I: Iterator<Item=isize>> SlidingWindow<I> {
+    pub fn new(depths: I, width: usize) -> Self {
+        assert!(width > 0, "Sliding windows must have at least one depth");
+        SlidingWindow {depths, width, window: VecDeque::with_capacity(width), sum: 0, mins: VecDeque::new(), maxes: VecDeque::new()}
+    }
+
+    fn push(&mut self, depth: isize) {
+        self.window.push_back(depth);
+        self.sum += depth;
+        while self.mins.back().map_or(false, |m| *m > depth) {
+            self.mins.pop_back();
+        }
+        self.mins.push_back(depth);
+        while self.maxes.back().map_or(false, |m| *m < depth) {
+            self.maxes.pop_back();
+        }
+        self.maxes.push_back(depth);
+    }
+
+    fn pop(&mut self) {
+        if let Some(depth) = self.window.pop_front() {
+            self.sum -= depth;
+            if self.mins.front() == Some(&depth) {
+                self.mins.pop_front();
+            }
+            if self.maxes.front() == Some(&depth) {
+                self.maxes.pop_front();
+            }
+        }
+    }
+}
+
+impl<I: Iterator<Item=isize>> Iterator for SlidingWindow<I> {
+    type Item = Window;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.window.len() == self.width {
+            self.pop();
+        }
+        while self.window.len() < self.width {
+            let depth = self.depths.next()?;
+            self.push(depth);
+        }
+        Some(Window {width: self.width, sum: self.sum, min: self.mins[0], max: self.maxes[0]})
+    }
+}
+
+pub trait SlidingWindows: Iterator<Item=isize> + Sized {
+    fn sliding_windows(self, width: usize) -> SlidingWindow<Self> {
+        SlidingWindow::new(self, width)
+    }
+}
+
+impl<I: Iterator<Item=isize>> SlidingWindows for I {}
+
+/// How each value compares to the one before it.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub struct Trends {
+    pub increases: usize,
+    pub decreases: usize,
+    pub plateaus: usize
+}
+
+impl Trends {
+    pub fn of<T: PartialOrd, I: Iterator<Item=T>>(values: I) -> Self {
+        let mut trends = Trends::default();
+        let mut previous = None;
+        for value in values {
+            if let Some(previous) = previous {
+                if value > previous {
+                    trends.increases += 1;
+                } else if value < previous {
+                    trends.decreases += 1;
+                } else {
+                    trends.plateaus += 1;
+                }
+            }
+            previous = Some(value);
+        }
+        trends
+    }
+}
+
+impl Display for Trends {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} increases, {} decreases, {} plateaus", self.increases, self.decreases, self.plateaus)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use quickcheck_macros::quickcheck;
+    use super::*;
+
+    #[test]
+    fn test_windows() {
+        let windows = [3, 1, 4, 1, 5].into_iter().sliding_windows(3).collect::<Vec<_>>();
+        assert_eq!(windows, vec![
+            Window {width: 3, sum: 8, min: 1, max: 4},
+            Window {width: 3, sum: 6, min: 1, max: 4},
+            Window {width: 3, sum: 10, min: 1, max: 5}
+        ]);
+        assert_eq!([1, 2].into_iter().sliding_windows(3).count(), 0);
+    }
+
+    #[quickcheck]
+    fn prop_matches_recomputed(depths: Vec<i16>, width: u8) -> bool {
+        let depths = depths.iter().map(|d| *d as isize).collect::<Vec<_>>();
+        let width = 1 + width as usize % 5;
+        let expected = depths.windows(width).map(|w| Window {
+            width, sum: w.iter().sum(), min: *w.iter().min().unwrap(), max: *w.iter().max().unwrap()
+        });
+        depths.iter().copied().sliding_windows(width).eq(expected)
+    }
+
+    #[test]
+    fn test_analyze() {
+        let lines = ["199", "200", "208", "210", "200", "207", "240", "269", "260", "263"].map(|s| s.to_string());
+        let trends = analyze(lines.iter().cloned(), 1, Aggregate::Sum).unwrap();
+        assert_eq!(trends, Trends {increases: 7, decreases: 2, plateaus: 0});
+        let trends = analyze(lines.iter().cloned(), 3, Aggregate::Max).unwrap();
+        assert_eq!(trends, Trends {increases: 3, decreases: 0, plateaus: 4});
+        let error = analyze(["1", "two"].iter().map(|s| s.to_string()), 1, Aggregate::Sum).unwrap_err();
+        assert_eq!(error.to_string(), "2: expected a number, found 'two'");
+    }
+}
@@ -0,0 +1,420 @@
+use std::iter::successors;
+use std::rc::Rc;
+use std::str::FromStr;
+use anyhow::bail;
+use crate::parse_error::ParseError;
+use crate::Solver;
+
+const OPENERS: [char; 4] = ['(', '[', '{', '<'];
+const CLOSERS: [char; 4] = [')', ']', '}', '>'];
+const PENALTIES: [usize; 4] = [3, 57, 1197, 25137];
+
+pub struct Day10;
+
+impl Solver for Day10 {
+    type Input = Vec<String>;
+    type Part1 = usize;
+    type Part2 = usize;
+
+    fn parse_lines<I: Iterator<Item=String>>(lines: I) -> anyhow::Result<Self::Input> {
+        Ok(lines.collect())
+    }
+
+    fn part1(input: &Self::Input) -> anyhow::Result<Self::Part1> {
+        Ok(part_1(input))
+    }
+
+    fn part2(input: &Self::Input) -> anyhow::Result<Self::Part2> {
+        part_2(input)
+    }
+}
+
+pub fn part_1(lines: &Vec<String>) -> usize {
+    let grammar = Grammar::standard();
+    lines.iter()
+        .filter_map(|line| grammar.corruption_score(&grammar.check(line.as_str())))
+        .sum()
+}
+
+pub fn part_2(lines: &Vec<String>) -> anyhow::Result<usize> {
+    let grammar = Grammar::standard();
+    let mut scores: Vec<usize> = lines.iter()
+        .filter_map(|line| grammar.completion_score(&grammar.check(line.as_str())))
+        .collect();
+    if scores.is_empty() {
+        bail!("no incomplete lines");
+    }
+    scores.sort();
+    Ok(scores[scores.len() / 2])
+}
+
+/// Which characters open and close nested groups, and which delimit strings whose
+/// contents are not checked. Any other character is ignored.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Grammar {
+    pairs: Vec<(char, char)>,
+    penalties: Vec<usize>,
+    quotes: Vec<char>,
+    escape: Option<char>
+}
+
+impl Grammar {
+    /// Each corruption costs 1 unless `with_penalties` says otherwise.
+    pub fn new(pairs: &[(char, char)]) -> Self {
+        Grammar {pairs: pairs.to_vec(), penalties: Vec::new(), quotes: Vec::new(), escape: None}
+    }
+
+    pub fn standard() -> Self {
+        let pairs = OPENERS.iter().copied().zip(CLOSERS.iter().copied()).collect::<Vec<_>>();
+        Grammar::new(&pairs).with_penalties(&PENALTIES)
+    }
+
+    /// The cost of finding each pair's closer out of place, in the order the pairs were given.
+    pub fn with_penalties(mut self, penalties: &[usize]) -> Self {
+        self.penalties = penalties.to_vec();
+        self
+    }
+
+    /// A string runs from a quote character to the next unescaped occurrence of the same one.
+    pub fn with_quotes(mut self, quotes: &[char]) -> Self {
+        self.quotes = quotes.to_vec();
+        self
+    }
+
+    /// The character after `escape` is ignored, whether or not it is in a string.
+    pub fn with_escape(mut self, escape: char) -> Self {
+        self.escape = Some(escape);
+        self
+    }
+
+    fn opener(&self, c: char) -> Option<usize> {
+        self.pairs.iter().position(|(open, _)| *open == c)
+    }
+
+    fn closer(&self, c: char) -> Option<usize> {
+        self.pairs.iter().position(|(_, close)| *close == c)
+    }
+
+    /// Reports every out-of-place closer. After one, checking resumes as if the closers of any
+    /// groups opened since its own opener had been missing; a closer with no opener is dropped.
+    pub fn check(&self, line: &str) -> SyntaxReport {
+        let mut checker = BracketChecker::new(self);
+        checker.push_str(line);
+        checker.report()
+    }
+
+    pub fn penalty_for(&self, closer: char) -> usize {
+        self.closer(closer).and_then(|pair| self.penalties.get(pair)).copied().unwrap_or(1)
+    }
+
+    /// The penalty for the first corruption, if there is one.
+    pub fn corruption_score(&self, report: &SyntaxReport) -> Option<usize> {
+        report.corruptions.first().map(|corruption| self.penalty_for(corruption.found))
+    }
+
+    /// Reads an uncorrupted line's completion as a number, with each pair's closer as a digit
+    /// from 1 upwards in the order of the pairs, followed by the quotes.
+    pub fn completion_score(&self, report: &SyntaxReport) -> Option<usize> {
+        if report.is_corrupted() {
+            return None;
+        }
+        Some(self.score_completion(report.completion.as_str()))
+    }
+
+    fn score_completion(&self, completion: &str) -> usize {
+        let radix = self.pairs.len() + self.quotes.len() + 1;
+        completion.chars()
+            .map(|c| 1 + self.closer(c)
+                .or_else(|| self.quotes.iter().position(|q| *q == c).map(|q| self.pairs.len() + q))
+                .unwrap())
+            .fold(0, |score, digit| score * radix + digit)
+    }
+}
+
+impl FromStr for Grammar {
+    type Err = ParseError;
+
+    /// Consecutive characters pair up as opener and closer, as in `()[]`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let chars = s.chars().collect::<Vec<_>>();
+        if chars.len() % 2 == 1 || chars.chunks(2).any(|pair| pair[0] == pair[1]) {
+            return Err(ParseError::expected("pairs of distinct characters", s));
+        }
+        Ok(Grammar::new(&chars.chunks(2).map(|pair| (pair[0], pair[1])).collect::<Vec<_>>()))
+    }
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct Corruption {
+    /// Starts at 1.
+    pub column: usize,
+    /// The closer for the innermost open group, if any group was open.
+    pub expected: Option<char>,
+    pub found: char
+}
+
+impl Corruption {
+    pub fn to_parse_error(&self) -> ParseError {
+        let expected = match self.expected {
+            Some(closer) => format!("'{}'", closer),
+            None => "nothing to close".to_string()
+        };
+        ParseError::expected(expected.as_str(), self.found.to_string().as_str()).at_column(self.column)
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SyntaxReport {
+    pub corruptions: Vec<Corruption>,
+    /// The shortest string that closes everything left open.
+    pub completion: String
+}
+
+impl SyntaxReport {
+    pub fn is_corrupted(&self) -> bool {
+        !self.corruptions.is_empty()
+    }
+}
+
+/// Checks a line as it arrives, a character or a chunk at a time, in the same way as `Grammar::check`.
+/// Clones share what has been read so far, so they are a cheap way to try out continuations.
+#[derive(Clone, Debug)]
+pub struct BracketChecker<'a> {
+    grammar: &'a Grammar,
+    open: OpenGroups,
+    quote: Option<char>,
+    escaped: bool,
+    column: usize,
+    corruptions: Rc<Vec<Corruption>>
+}
+
+impl <'a> BracketChecker<'a> {
+    pub fn new(grammar: &'a Grammar) -> Self {
+        BracketChecker {grammar, open: OpenGroups::default(), quote: None, escaped: false, column: 0,
+            corruptions: Rc::new(Vec::new())}
+    }
+
+    pub fn push(&mut self, c: char) {
+        self.column += 1;
+        if self.escaped {
+            self.escaped = false;
+        } else if self.grammar.escape == Some(c) {
+            self.escaped = true;
+        } else if let Some(q) = self.quote {
+            if c == q {
+                self.quote = None;
+            }
+        } else if self.grammar.quotes.contains(&c) {
+            self.quote = Some(c);
+        } else if let Some(pair) = self.grammar.opener(c) {
+            self.open.push(pair);
+        } else if let Some(pair) = self.grammar.closer(c) {
+            let innermost = self.open.iter().next();
+            if innermost != Some(pair) {
+                let expected = innermost.map(|open| self.grammar.pairs[open].1);
+                Rc::make_mut(&mut self.corruptions).push(Corruption {column: self.column, expected, found: c});
+            }
+            self.open.close(pair);
+        }
+    }
+
+    pub fn push_str(&mut self, chunk: &str) {
+        for c in chunk.chars() {
+            self.push(c);
+        }
+    }
+
+    /// The openers of the groups still open, outermost first.
+    pub fn stack(&self) -> Vec<char> {
+        let mut openers = self.open.iter().map(|pair| self.grammar.pairs[pair].0).collect::<Vec<_>>();
+        openers.reverse();
+        openers
+    }
+
+    pub fn corruptions(&self) -> &[Corruption] {
+        self.corruptions.as_slice()
+    }
+
+    pub fn is_corrupted(&self) -> bool {
+        !self.corruptions.is_empty()
+    }
+
+    pub fn completion(&self) -> String {
+        let mut completion = self.quote.iter().collect::<String>();
+        completion.extend(self.open.iter().map(|pair| self.grammar.pairs[pair].1));
+        completion
+    }
+
+    pub fn corruption_score(&self) -> Option<usize> {
+        self.corruptions.first().map(|corruption| self.grammar.penalty_for(corruption.found))
+    }
+
+    pub fn completion_score(&self) -> Option<usize> {
+        if self.is_corrupted() {None} else {Some(self.grammar.score_completion(self.completion().as_str()))}
+    }
+
+    pub fn report(&self) -> SyntaxReport {
+        SyntaxReport {corruptions: self.corruptions.to_vec(), completion: self.completion()}
+    }
+}
+
+/// Pair indices of the open groups, innermost first, with clones sharing the groups they have in common.
+#[derive(Clone, Debug, Default)]
+struct OpenGroups(Option<Rc<OpenGroup>>);
+
+#[derive(Debug)]
+struct OpenGroup {
+    pair: usize,
+    enclosing: OpenGroups
+}
+
+impl OpenGroups {
+    fn push(&mut self, pair: usize) {
+        let enclosing = self.clone();
+        self.0 = Some(Rc::new(OpenGroup {pair, enclosing}));
+    }
+
+    fn iter(&self) -> impl Iterator<Item=usize> + '_ {
+        successors(self.0.as_deref(), |group| group.enclosing.0.as_deref()).map(|group| group.pair)
+    }
+
+    /// Closes the innermost group of `pair` along with every group inside it, if one is open.
+    fn close(&mut self, pair: usize) {
+        if self.iter().any(|open| open == pair) {
+            while let Some(group) = self.0.clone() {
+                *self = group.enclosing.clone();
+                if group.pair == pair {
+                    return;
+                }
+            }
+        }
+    }
+}
+
+/// Drops a long chain of groups one at a time, rather than recursing once per group.
+impl Drop for OpenGroups {
+    fn drop(&mut self) {
+        let mut next = self.0.take();
+        while let Some(group) = next {
+            next = match Rc::try_unwrap(group) {
+                Ok(mut group) => group.enclosing.0.take(),
+                Err(_) => None
+            };
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_corruption() {
+        let grammar = Grammar::standard();
+        let report = grammar.check("{([(<{}[<>[]}>{[]{[(<()>");
+        assert_eq!(report.corruptions[0], Corruption {column: 13, expected: Some(']'), found: '}'});
+        assert_eq!(grammar.corruption_score(&report), Some(1197));
+        assert_eq!(grammar.completion_score(&report), None);
+        let report = grammar.check("[({(<(())[]>[[{[]{<()<>>");
+        assert_eq!(report.completion, "}}]])})]");
+        assert_eq!(grammar.completion_score(&report), Some(288957));
+    }
+
+    #[test]
+    fn test_recovery() {
+        let grammar = Grammar::standard();
+        let report = grammar.check("(]<[>)>");
+        assert_eq!(report.corruptions, vec![
+            Corruption {column: 2, expected: Some(')'), found: ']'},
+            Corruption {column: 5, expected: Some(']'), found: '>'},
+            Corruption {column: 7, expected: None, found: '>'}
+        ]);
+        assert_eq!(report.completion, "");
+        assert_eq!(grammar.check("){").corruptions[0].to_parse_error().to_string(), "1: expected nothing to close, found ')'");
+    }
+
+    #[test]
+    fn test_custom_grammar() {
+        let grammar = "()".parse::<Grammar>().unwrap().with_quotes(&['"']).with_escape('\\');
+        let report = grammar.check(r#"(key "va)lue \" (" \) "("#);
+        assert!(!report.is_corrupted());
+        assert_eq!(report.completion, "\")");
+        assert_eq!(grammar.completion_score(&report), Some(2 * 3 + 1));
+        let report = grammar.check("a) (b))");
+        assert_eq!(report.corruptions.iter().map(|c| c.column).collect::<Vec<_>>(), vec![2, 7]);
+        assert_eq!(grammar.corruption_score(&report), Some(1));
+        assert!("(((".parse::<Grammar>().is_err());
+        assert!("||".parse::<Grammar>().is_err());
+    }
+
+    #[test]
+    fn test_checker() {
+        let grammar = Grammar::standard();
+        let expected = [
+            (None, Some(288957)),
+            (None, Some(5566)),
+            (Some((']', '}', 1197)), None),
+            (None, Some(1480781)),
+            (Some((']', ')', 3)), None),
+            (Some((')', ']', 57)), None),
+            (None, Some(995444)),
+            (Some(('>', ')', 3)), None),
+            (Some((']', '>', 25137)), None),
+            (None, Some(294))
+        ];
+        let lines = crate::input_lines("ex/day10.txt").unwrap().collect::<Vec<_>>();
+        assert_eq!(lines.len(), expected.len());
+        for (line, (corruption, completion_score)) in lines.iter().zip(expected) {
+            let mut checker = BracketChecker::new(&grammar);
+            for chunk in line.as_bytes().chunks(3) {
+                checker.push_str(std::str::from_utf8(chunk).unwrap());
+            }
+            let first = checker.corruptions().first().map(|c| (c.expected.unwrap(), c.found));
+            assert_eq!(first, corruption.map(|(expected, found, _)| (expected, found)), "{}", line);
+            assert_eq!(checker.corruption_score(), corruption.map(|(_, _, score)| score), "{}", line);
+            assert_eq!(checker.completion_score(), completion_score, "{}", line);
+        }
+    }
+
+    #[test]
+    fn test_deeply_nested() {
+        let grammar = Grammar::standard();
+        let mut checker = BracketChecker::new(&grammar);
+        checker.push_str("(".repeat(1_000_000).as_str());
+        let mut shared = checker.clone();
+        shared.push(')');
+        assert_eq!(checker.stack().len(), 1_000_000);
+        drop(checker);
+        assert_eq!(shared.stack().len(), 999_999);
+    }
+
+    #[test]
+    fn test_what_if() {
+        let grammar = Grammar::standard();
+        let mut checker = BracketChecker::new(&grammar);
+        checker.push_str("[({(<");
+        assert_eq!(checker.stack(), vec!['[', '(', '{', '(', '<']);
+        let mut closed = checker.clone();
+        closed.push_str(">)");
+        assert_eq!(closed.stack(), vec!['[', '(', '{']);
+        assert_eq!(closed.completion(), "})]");
+        let mut corrupted = checker.clone();
+        corrupted.push('}');
+        assert_eq!(corrupted.corruption_score(), Some(1197));
+        assert_eq!(corrupted.stack(), vec!['[', '(']);
+        assert_eq!(checker.completion(), ">)})]");
+        assert_eq!(checker.completion_score(), Some(((((4 * 5) + 1) * 5 + 3) * 5 + 1) * 5 + 2));
+        assert!(!checker.is_corrupted());
+    }
+
+    #[test]
+    fn test_examples() {
+        crate::answers::assert_examples(10);
+    }
+
+    #[test]
+    fn test_nothing_to_complete() {
+        let lines = vec!["(]".to_string(), "<)".to_string()];
+        assert_eq!(Day10::part2(&lines).unwrap_err().to_string(), "no incomplete lines");
+    }
+}
\ No newline at end of file
@@ -0,0 +1,289 @@
+use std::collections::{HashMap, VecDeque};
+use std::fmt::{Display, Formatter};
+use advent_code_lib::Position;
+use anyhow::{anyhow, bail};
+use bare_metal_modulo::MNum;
+use crate::{input_lines, Solver};
+use crate::grid::Neighbourhood;
+use crate::parse_error::digit_grid;
+
+pub struct Day11;
+
+impl Solver for Day11 {
+    type Input = DumboOctopi;
+    type Part1 = usize;
+    type Part2 = usize;
+
+    fn parse_lines<I: Iterator<Item=String>>(lines: I) -> anyhow::Result<Self::Input> {
+        DumboOctopi::from_iter(lines)
+    }
+
+    fn part1(input: &Self::Input) -> anyhow::Result<Self::Part1> {
+        Ok(part_1(input.clone()))
+    }
+
+    fn part2(input: &Self::Input) -> anyhow::Result<Self::Part2> {
+        match part_2(input.clone()) {
+            Synchronisation::At(step) => Ok(step),
+            never => Err(anyhow!("{}", never))
+        }
+    }
+}
+
+const PART_1_ITERATIONS: usize = 100;
+const DEFAULT_THRESHOLD: usize = 10;
+
+pub fn part_1(octopi: DumboOctopi) -> usize {
+    octopi.take(PART_1_ITERATIONS).sum()
+}
+
+pub fn part_2(octopi: DumboOctopi) -> Synchronisation {
+    octopi.synchronisation()
+}
+
+pub fn show_steps(mut octopi: DumboOctopi, steps: usize) {
+    println!("Before any steps:");
+    println!("{}", octopi);
+    println!();
+
+    for step in 1..=steps {
+        let flashes = octopi.next().unwrap();
+        println!("After step {} ({} flashes):", step, flashes);
+        println!("{}", octopi);
+        println!();
+    }
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct Cycle {
+    /// The number of steps before the first state that recurs.
+    pub pre_period: usize,
+    pub period: usize
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Synchronisation {
+    /// The first step on which every octopus flashes.
+    At(usize),
+    /// The grid returned to an earlier state without every octopus ever flashing at once.
+    Never(Cycle)
+}
+
+impl Display for Synchronisation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Synchronisation::At(step) => write!(f, "{}", step),
+            Synchronisation::Never(cycle) => write!(f, "no synchronisation, cycle of length {} after {} steps",
+                                                    cycle.period, cycle.pre_period)
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct DumboOctopi {
+    energies: Vec<usize>,
+    width: usize,
+    height: usize,
+    threshold: usize,
+    neighbourhood: Neighbourhood,
+    wrap_around: bool
+}
+
+impl DumboOctopi {
+    pub fn new(filename: &str) -> anyhow::Result<DumboOctopi> {
+        Self::from_iter(input_lines(filename)?)
+    }
+
+    pub fn from_iter<I: Iterator<Item=String>>(lines: I) -> anyhow::Result<DumboOctopi> {
+        let world = digit_grid(lines)?;
+        let energies = world.position_iter()
+            .map(|p| world.value(p).map(|e| e.a() as usize).ok_or(anyhow!("No octopus at {}", p)))
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        Ok(DumboOctopi {energies, width: world.width(), height: world.height(), threshold: DEFAULT_THRESHOLD,
+            neighbourhood: Neighbourhood::Eight, wrap_around: false})
+    }
+
+    /// An octopus flashes once its energy reaches `threshold`.
+    pub fn with_threshold(mut self, threshold: usize) -> Self {
+        self.threshold = threshold;
+        self
+    }
+
+    pub fn with_neighbourhood(mut self, neighbourhood: Neighbourhood) -> anyhow::Result<Self> {
+        self.neighbourhood = neighbourhood;
+        self.checked()
+    }
+
+    /// Joins opposite edges, making the grid a torus. Wrapping a hex grid needs an even number of rows.
+    pub fn with_wrap_around(mut self, wrap_around: bool) -> anyhow::Result<Self> {
+        self.wrap_around = wrap_around;
+        self.checked()
+    }
+
+    fn checked(self) -> anyhow::Result<Self> {
+        if self.wrap_around && !self.neighbourhood.wraps_vertically(self.height) {
+            bail!("Cannot wrap a {:?} grid with {} rows around", self.neighbourhood, self.height);
+        }
+        Ok(self)
+    }
+
+    pub fn len(&self) -> usize {
+        self.energies.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.energies.is_empty()
+    }
+
+    /// Each distinct neighbouring octopus once, even where a small torus wraps onto itself.
+    fn neighbors(&self, i: usize) -> Vec<usize> {
+        let (width, height) = (self.width as isize, self.height as isize);
+        let p = Position::from(((i % self.width) as isize, (i / self.width) as isize));
+        let mut neighbors = self.neighbourhood.neighbors(p).into_iter()
+            .filter_map(|n| if self.wrap_around {
+                Some(Position::from((n.col.rem_euclid(width), n.row.rem_euclid(height))))
+            } else if (0..width).contains(&n.col) && (0..height).contains(&n.row) {
+                Some(n)
+            } else {
+                None
+            })
+            .map(|n| (n.row * width + n.col) as usize)
+            .filter(|n| *n != i)
+            .collect::<Vec<_>>();
+        neighbors.sort_unstable();
+        neighbors.dedup();
+        neighbors
+    }
+
+    pub fn synchronisation(&self) -> Synchronisation {
+        let all = self.len();
+        match self.steps_until(|flashes| flashes == all) {
+            Ok(step) => Synchronisation::At(step),
+            Err(cycle) => Synchronisation::Never(cycle)
+        }
+    }
+
+    /// The cycle the grid eventually falls into, which may be long for large thresholds.
+    pub fn flash_cycle(&self) -> Cycle {
+        self.steps_until(|_| false).unwrap_err()
+    }
+
+    /// Runs a copy until `done` holds for the flashes in a step, returning that step, or
+    /// until the copy repeats a state, so that `done` can never hold.
+    fn steps_until<F: Fn(usize) -> bool>(&self, done: F) -> Result<usize, Cycle> {
+        let mut octopi = self.clone();
+        let mut seen = HashMap::from([(octopi.energies.clone(), 0)]);
+        for step in 1.. {
+            let flashes = octopi.next().unwrap();
+            if done(flashes) {
+                return Ok(step);
+            }
+            if let Some(earlier) = seen.insert(octopi.energies.clone(), step) {
+                return Err(Cycle {pre_period: earlier, period: step - earlier});
+            }
+        }
+        unreachable!()
+    }
+}
+
+impl Iterator for DumboOctopi {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut flashed = vec![false; self.len()];
+        let mut queue = VecDeque::new();
+        for (i, energy) in self.energies.iter_mut().enumerate() {
+            *energy += 1;
+            if *energy >= self.threshold {
+                flashed[i] = true;
+                queue.push_back(i);
+            }
+        }
+        let mut flashes = 0;
+        while let Some(flasher) = queue.pop_front() {
+            flashes += 1;
+            for neighbor in self.neighbors(flasher) {
+                if !flashed[neighbor] {
+                    self.energies[neighbor] += 1;
+                    if self.energies[neighbor] >= self.threshold {
+                        flashed[neighbor] = true;
+                        queue.push_back(neighbor);
+                    }
+                }
+            }
+        }
+        for (energy, flashed) in self.energies.iter_mut().zip(flashed) {
+            if flashed {
+                *energy = 0;
+            }
+        }
+        Some(flashes)
+    }
+}
+
+impl Display for DumboOctopi {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for (i, energy) in self.energies.iter().enumerate() {
+            if i > 0 && i % self.width == 0 {writeln!(f)?;}
+            write!(f, "{}", char::from_digit(*energy as u32, 36).unwrap_or('*'))?
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn octopi(grid: &str) -> DumboOctopi {
+        DumboOctopi::from_iter(grid.lines().map(|line| line.to_string())).unwrap()
+    }
+
+    #[test]
+    fn test_example() {
+        let octopi = DumboOctopi::new("ex/day11.txt").unwrap();
+        assert_eq!(part_1(octopi.clone()), 1656);
+        assert_eq!(part_2(octopi.clone()), Synchronisation::At(195));
+        let cycle = octopi.flash_cycle();
+        assert_eq!(cycle.period, DEFAULT_THRESHOLD);
+        assert!(cycle.pre_period <= 195);
+        let mut tiny = DumboOctopi::new("ex/day11tiny.txt").unwrap();
+        assert_eq!(tiny.nth(1), Some(0));
+        assert_eq!(tiny.to_string(), "45654\n51115\n61116\n51115\n45654");
+    }
+
+    #[test]
+    fn test_never_synchronises() {
+        let pair = octopi("05").with_neighbourhood(Neighbourhood::Four).unwrap();
+        let never = Synchronisation::Never(Cycle {pre_period: 0, period: 9});
+        assert_eq!(pair.synchronisation(), never);
+        assert_eq!(never.to_string(), "no synchronisation, cycle of length 9 after 0 steps");
+        assert!(Day11::part2(&pair).is_err());
+    }
+
+    #[test]
+    fn test_configurations() {
+        let mut low = octopi("000\n000").with_threshold(2);
+        assert_eq!(low.next(), Some(0));
+        assert_eq!(low.next(), Some(6));
+        assert_eq!(low.flash_cycle(), Cycle {pre_period: 0, period: 2});
+
+        let corner = octopi("900\n000\n000");
+        assert_eq!(corner.clone().next(), Some(1));
+        let mut wrapped = corner.with_wrap_around(true).unwrap();
+        wrapped.next();
+        assert_eq!(wrapped.to_string(), "022\n222\n222");
+
+        let mut hex = octopi("000\n090\n000").with_neighbourhood(Neighbourhood::Hex).unwrap();
+        hex.next();
+        assert_eq!(hex.to_string(), "122\n202\n122");
+        assert_eq!(octopi("0").with_wrap_around(true).unwrap().neighbors(0), Vec::<usize>::new());
+        assert_eq!(octopi("00").with_wrap_around(true).unwrap().neighbors(0), vec![1]);
+
+        let hex = || octopi("000\n000\n000").with_neighbourhood(Neighbourhood::Hex).unwrap();
+        assert_eq!(hex().with_wrap_around(true).unwrap_err().to_string(), "Cannot wrap a Hex grid with 3 rows around");
+        let wrapped = octopi("000\n000\n000").with_wrap_around(true).unwrap();
+        assert!(wrapped.with_neighbourhood(Neighbourhood::Hex).is_err());
+        assert!(octopi("00\n00").with_neighbourhood(Neighbourhood::Hex).unwrap().with_wrap_around(true).is_ok());
+    }
+}
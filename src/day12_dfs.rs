@@ -0,0 +1,112 @@
+use advent_code_lib::{AdjacencySets, Arena, ParentMapQueue, search, SearchQueue, ContinueSearch};
+use hash_histogram::HashHistogram;
+use crate::{input_lines, Solver};
+use crate::parse_error::{ParseError, ParseResult};
+
+// NOTE:
+// * No big cave is ever connected to another big cave!
+// * If 2 big caves were connected, you could bounce between them indefinitely, leading
+//   to an infinite number of paths.
+
+const START: &'static str = "start";
+const END: &'static str = "end";
+
+pub struct Day12Dfs;
+
+impl Solver for Day12Dfs {
+    type Input = AdjacencySets;
+    type Part1 = usize;
+    type Part2 = usize;
+
+    fn parse_lines<I: Iterator<Item=String>>(lines: I) -> anyhow::Result<Self::Input> {
+        Ok(graph_from_lines(lines)?)
+    }
+
+    fn part1(input: &Self::Input) -> anyhow::Result<Self::Part1> {
+        Ok(all_paths(input, Rule::Part1).len())
+    }
+
+    fn part2(input: &Self::Input) -> anyhow::Result<Self::Part2> {
+        Ok(all_paths(input, Rule::Part2).len())
+    }
+}
+
+pub fn build_graph_from(filename: &str) -> ParseResult<AdjacencySets> {
+    graph_from_lines(input_lines(filename)?).map_err(|e| e.in_file(filename))
+}
+
+pub fn graph_from_lines<I: Iterator<Item=String>>(lines: I) -> ParseResult<AdjacencySets> {
+    let mut graph = AdjacencySets::new();
+    for (i, line) in lines.enumerate() {
+        let (a, b) = line.split_once('-').ok_or(ParseError::expected("'cave-cave'", line.as_str()).at_line(i + 1))?;
+        graph.connect2(a, b);
+    }
+    Ok(graph)
+}
+
+pub fn has_upper(s: &str) -> bool {
+    s.chars().any(|c| c.is_uppercase())
+}
+
+pub fn show(paths: &Vec<Vec<String>>) {
+    for path in paths.iter() {
+        println!("{:?}", path);
+    }
+}
+
+pub fn all_paths(graph: &AdjacencySets, rule: Rule) -> Vec<Vec<String>> {
+    let mut all_paths = Vec::new();
+    let mut arena = Arena::new();
+    let mut stack: ParentMapQueue<usize, Vec<usize>> = ParentMapQueue::new();
+    stack.enqueue(&(arena.alloc(START.to_string(), None)));
+    search(stack, |node, stack| {
+        let parent = stack.parent_of(node);
+        let last_name = arena.get(*node).get().as_str();
+        if rule.allows(&arena, last_name, *parent) {
+            if last_name == END {
+                all_paths.push(path_at_addr(&arena, *node));
+            } else {
+                for neighbor in graph.neighbors_of(last_name).unwrap() {
+                    let new_addr = arena.alloc(neighbor.clone(), Some(*node));
+                    stack.enqueue(&new_addr);
+                }
+            }
+        }
+        ContinueSearch::Yes
+    });
+    all_paths
+}
+
+fn path_at_addr(arena: &Arena<String>, addr: usize) -> Vec<String> {
+    let mut path = arena.iter_from(addr).cloned().collect::<Vec<_>>();
+    path.reverse();
+    path
+}
+
+#[derive(Copy, Clone, Debug)]
+pub enum Rule {
+    Part1, Part2
+}
+
+impl Rule {
+    fn allows(&self, arena: &Arena<String>, node: &str, parent: Option<usize>) -> bool {
+        match parent {
+            None => true,
+            Some(parent_addr) => {
+                let mut path_counts: HashHistogram<String> = arena.iter_from(parent_addr).collect();
+                path_counts.bump(&node.to_string());
+                let mut potential_problems = path_counts.iter()
+                    .filter(|(s, c)| !has_upper(s.as_str()) && **c > 1);
+                match self {
+                    Rule::Part1 => potential_problems.next().is_none(),
+                    Rule::Part2 => {
+                        let problems = potential_problems.collect::<Vec<_>>();
+                        problems.iter().find(|(s, _)| [START, END].contains(&s.as_str())).is_none()
+                            && !problems.iter().any(|(_, count)| **count > 2)
+                            && problems.len() <= 1
+                    }
+                }
+            }
+        }
+    }
+}
\ No newline at end of file
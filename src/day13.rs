@@ -0,0 +1,166 @@
+use std::collections::HashSet;
+use std::str::FromStr;
+use advent_code_lib::{Position, RowMajorPositionIterator};
+use crate::{input_lines, Solver};
+use crate::parse_error::{numbers_in, parse_number, ParseError, ParseResult};
+
+const FOLD_PREFIX: &'static str = "fold along ";
+
+pub struct Day13;
+
+impl Solver for Day13 {
+    type Input = (HashSet<Position>, Vec<FoldInstruction>);
+    type Part1 = usize;
+    type Part2 = String;
+
+    fn parse_lines<I: Iterator<Item=String>>(lines: I) -> anyhow::Result<Self::Input> {
+        Ok(input_from(lines)?)
+    }
+
+    fn part1(input: &Self::Input) -> anyhow::Result<Self::Part1> {
+        Ok(one_fold_count(&input.0, &input.1))
+    }
+
+    fn part2(input: &Self::Input) -> anyhow::Result<Self::Part2> {
+        Ok(render(&fold_all(&input.0, &input.1)))
+    }
+}
+
+pub fn parse_input(filename: &str) -> ParseResult<(HashSet<Position>, Vec<FoldInstruction>)> {
+    input_from(input_lines(filename)?).map_err(|e| e.in_file(filename))
+}
+
+pub fn input_from<I: Iterator<Item=String>>(lines: I) -> ParseResult<(HashSet<Position>, Vec<FoldInstruction>)> {
+    let mut lines = lines.enumerate();
+    let mut points = HashSet::new();
+    for (i, line) in lines.by_ref().take_while(|(_, line)| line.len() > 0) {
+        points.insert(point_from(line.as_str()).map_err(|e| e.at_line(i + 1))?);
+    }
+    let mut instructions = Vec::new();
+    for (i, line) in lines {
+        instructions.push(line.parse().map_err(|e: ParseError| e.at_line(i + 1))?);
+    }
+    Ok((points, instructions))
+}
+
+fn point_from(line: &str) -> ParseResult<Position> {
+    match numbers_in::<isize>(line, ',')?.as_slice() {
+        [x, y] => Ok(Position::from((*x, *y))),
+        _ => Err(ParseError::expected("'x,y'", line))
+    }
+}
+
+pub fn one_fold_count(points: &HashSet<Position>, instructions: &Vec<FoldInstruction>) -> usize {
+    instructions[0].folded_points(points.iter()).len()
+}
+
+pub fn fold_all(points: &HashSet<Position>, instructions: &Vec<FoldInstruction>) -> HashSet<Position> {
+    instructions.iter().fold(points.clone(), |prev, instr| instr.folded_points(prev.iter()))
+}
+
+pub fn render(points: &HashSet<Position>) -> String {
+    let width = 1 + points.iter().map(|p| p.col as usize).max().unwrap();
+    let height = 1 + points.iter().map(|p| p.row as usize).max().unwrap();
+    let mut result = String::new();
+    for p in RowMajorPositionIterator::new(width, height) {
+        if p.col == 0 && p.row > 0 {
+            result.push('\n');
+        }
+        result.push(if points.contains(&p) {'#'} else {'.'});
+    }
+    result
+}
+
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum FoldInstruction {
+    Horizontal(isize),
+    Vertical(isize)
+}
+
+impl FromStr for FoldInstruction {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let fold = s.strip_prefix(FOLD_PREFIX).ok_or(ParseError::expected("'fold along'", s))?;
+        let (x_or_y, fold_spot) = fold.split_once('=')
+            .ok_or(ParseError::expected("'x=' or 'y='", fold).offset_by(FOLD_PREFIX.len()))?;
+        let fold_spot = parse_number(fold_spot).map_err(|e| e.offset_by(FOLD_PREFIX.len() + x_or_y.len() + 1))?;
+        match x_or_y {
+            "x" => Ok(FoldInstruction::Vertical(fold_spot)),
+            "y" => Ok(FoldInstruction::Horizontal(fold_spot)),
+            bad => Err(ParseError::expected("'x' or 'y'", bad).offset_by(FOLD_PREFIX.len()))
+        }
+    }
+}
+
+impl FoldInstruction {
+    fn remapped_value(fold_point: isize, original: isize) -> isize {
+        if original > fold_point {
+            2 * fold_point - original
+        } else {
+            original
+        }
+    }
+
+    fn folded_point(&self, p: Position) -> Position {
+        Position::from(match self {
+            FoldInstruction::Horizontal(y_fold) =>
+                (p.col, FoldInstruction::remapped_value(*y_fold, p.row)),
+            FoldInstruction::Vertical(x_fold) =>
+                (FoldInstruction::remapped_value(*x_fold, p.col), p.row)
+        })
+    }
+
+    fn folded_points<'a, I: Iterator<Item=&'a Position>>(&'a self, points: I) -> HashSet<Position> {
+        points.map(|p| self.folded_point(*p)).collect()
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use advent_code_lib::Position;
+    use quickcheck::{Arbitrary, Gen};
+    use quickcheck_macros::quickcheck;
+    use super::FoldInstruction;
+
+    impl Arbitrary for FoldInstruction {
+        fn arbitrary(g: &mut Gen) -> Self {
+            let spot = i16::arbitrary(g) as isize;
+            if bool::arbitrary(g) {FoldInstruction::Horizontal(spot)} else {FoldInstruction::Vertical(spot)}
+        }
+    }
+
+    #[quickcheck]
+    fn prop_fold_idempotent(fold: FoldInstruction, col: i16, row: i16) -> bool {
+        let folded = fold.folded_point(Position::from((col as isize, row as isize)));
+        fold.folded_point(folded) == folded
+    }
+
+    #[quickcheck]
+    fn prop_fold_keeps_side(fold: FoldInstruction, col: i16, row: i16) -> bool {
+        let p = Position::from((col as isize, row as isize));
+        let folded = fold.folded_point(p);
+        match fold {
+            FoldInstruction::Horizontal(y) => folded.col == p.col && folded.row <= y && (folded.row - y).abs() == (p.row - y).abs(),
+            FoldInstruction::Vertical(x) => folded.row == p.row && folded.col <= x && (folded.col - x).abs() == (p.col - x).abs()
+        }
+    }
+
+    #[test]
+    fn test() {
+        for (old, folder, folded) in [
+            ((6, 10), FoldInstruction::Horizontal(7), (6, 4))
+        ] {
+            assert_eq!(Position::from(folded), folder.folded_point(Position::from(old)));
+        }
+    }
+
+    #[test]
+    fn test_parse_fold() {
+        assert_eq!("fold along y=7".parse::<FoldInstruction>().unwrap(), FoldInstruction::Horizontal(7));
+        assert_eq!("fold along x=5".parse::<FoldInstruction>().unwrap(), FoldInstruction::Vertical(5));
+        let error = "fold along z=5".parse::<FoldInstruction>().unwrap_err();
+        assert_eq!(error.to_string(), "12: expected 'x' or 'y', found 'z'");
+    }
+}
\ No newline at end of file
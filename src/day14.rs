@@ -0,0 +1,139 @@
+use std::collections::HashMap;
+use hash_histogram::HashHistogram;
+use crate::{input_lines, Solver};
+use crate::parse_error::{next_line, ParseError, ParseResult};
+
+pub struct Day14;
+
+impl Solver for Day14 {
+    type Input = PolymerIterator;
+    type Part1 = usize;
+    type Part2 = usize;
+
+    fn parse_lines<I: Iterator<Item=String>>(lines: I) -> anyhow::Result<Self::Input> {
+        Ok(PolymerIterator::from_iter(lines)?)
+    }
+
+    fn part1(input: &Self::Input) -> anyhow::Result<Self::Part1> {
+        Ok(score_after(input, 10))
+    }
+
+    fn part2(input: &Self::Input) -> anyhow::Result<Self::Part2> {
+        Ok(score_after(input, 40))
+    }
+}
+
+pub fn score_after(polymer: &PolymerIterator, num_steps: usize) -> usize {
+    let polymer = polymer.clone();
+    polymer.skip(num_steps).next().unwrap()
+}
+
+#[derive(Debug, Clone)]
+pub struct PolymerIterator {
+    state: HashHistogram<(char,char)>,
+    final_letter: char,
+    rules: HashMap<(char,char), char>
+}
+
+impl PolymerIterator {
+    pub fn new(filename: &str) -> ParseResult<Self> {
+        Self::from_iter(input_lines(filename)?).map_err(|e| e.in_file(filename))
+    }
+
+    pub fn from_iter<I: Iterator<Item=String>>(mut lines: I) -> ParseResult<Self> {
+        let first_line = next_line(&mut lines, "a polymer template")?;
+        let pairs: Vec<(char, char)> = first_line.chars()
+            .zip(first_line.chars().skip(1))
+            .map(|(a, b)| (a, b))
+            .collect();
+        let state = pairs.iter().collect();
+        let final_letter = pairs.last()
+            .ok_or(ParseError::expected("at least two elements", first_line.as_str()).at_line(1))?.1;
+
+        lines.next();
+        let mut rules = HashMap::new();
+        for (i, line) in lines.enumerate() {
+            let (key, value) = rule_from(line.as_str()).map_err(|e| e.at_line(i + 3))?;
+            rules.insert(key, value);
+        }
+        Ok(PolymerIterator {state, final_letter, rules})
+    }
+}
+
+fn rule_from(line: &str) -> ParseResult<((char, char), char)> {
+    let (key, value) = line.split_once(" -> ").ok_or(ParseError::expected("'AB -> C'", line))?;
+    let key_chars: Vec<char> = key.chars().collect();
+    if key_chars.len() != 2 {
+        return Err(ParseError::expected("a pair of elements", key));
+    }
+    let mut value_chars = value.chars();
+    match (value_chars.next(), value_chars.next()) {
+        (Some(v), None) => Ok(((key_chars[0], key_chars[1]), v)),
+        _ => Err(ParseError::expected("a single element", value).offset_by(key.len() + 4))
+    }
+}
+
+impl Iterator for PolymerIterator {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let result = score_histogram(&self.state, self.final_letter);
+        let mut updated_state = HashHistogram::new();
+        for ((a, c), count) in self.state.iter() {
+            let b = self.rules.get(&(*a, *c)).unwrap();
+            updated_state.bump_by(&(*a, *b), *count);
+            updated_state.bump_by(&(*b, *c), *count);
+        }
+        assert_eq!(self.state.total_count() * 2, updated_state.total_count());
+        self.state = updated_state;
+        Some(result)
+    }
+}
+
+fn score_histogram(pair_counts: &HashHistogram<(char,char)>, final_letter: char) -> usize {
+    let mut histogram = HashHistogram::new();
+    for (pair, count) in pair_counts.iter() {
+        histogram.bump_by(&pair.0, *count);
+    }
+    histogram.bump(&final_letter);
+    assert_eq!(histogram.total_count(), pair_counts.total_count() + 1);
+    let ranked = histogram.ranking();
+    histogram.count(&ranked[0]) - histogram.count(&ranked[ranked.len() - 1])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{PolymerIterator, rule_from};
+
+    #[test]
+    fn test_example_1() {
+        for (skip, count) in [(0, 1), (1, 1), (2, 5), (10, 1588)].iter().copied() {
+            let polymer = PolymerIterator::new("ex/day14.txt").unwrap();
+            assert_eq!(polymer.skip(skip).next().unwrap(), count);
+        }
+    }
+
+    #[test]
+    fn test_examples() {
+        crate::answers::assert_examples(14);
+    }
+
+    #[test]
+    fn test_rule_errors() {
+        assert_eq!(rule_from("CH -> B").unwrap(), (('C', 'H'), 'B'));
+        assert_eq!(rule_from("CH => B").unwrap_err().to_string(), "expected 'AB -> C', found 'CH => B'");
+        assert_eq!(rule_from("CH -> BC").unwrap_err().to_string(), "7: expected a single element, found 'BC'");
+    }
+}
+
+// NNCB - NN: 1, NC: 1, CB: 1
+// Final pair: CB
+// 2 start w/N, 1 starts w/C, then add Bs from final pair
+// N: 2, C: 1, B: 1
+
+// NCNBCHB - NC: 1, CN: 1, NB: 1, BC: 1, CH: 1, HB: 1
+// Final pair: HB
+// 2 start w/N, 2 start w/C, 1 starts w/B, 1 starts w/H
+// N: 2, C: 2, B: 1 + 1 = 2, H: 1
+
+// NBCCNBBBCBHCB: NB: 2, BC: 2, CC: 1, CN: 1, BB: 2, CB: 2, BH: 1, HC: 1
\ No newline at end of file
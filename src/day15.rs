@@ -0,0 +1,176 @@
+use advent_code_lib::{Position, map_width_height, RowMajorPositionIterator, ManhattanDir, DirType, ContinueSearch, SearchResult, AStarQueue, best_first_search, SearchQueue, AStarCost, AStarNode};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fmt::{Display, Formatter};
+use anyhow::anyhow;
+use bare_metal_modulo::{MNum, ModNumC};
+use crate::{input_lines, Solver};
+use crate::parse_error::{digit_grid, ParseError};
+
+pub const EXPANSION_FACTOR: usize = 5;
+
+pub struct Day15;
+
+impl Solver for Day15 {
+    type Input = RiskMap;
+    type Part1 = u128;
+    type Part2 = u128;
+
+    fn parse_lines<I: Iterator<Item=String>>(lines: I) -> anyhow::Result<Self::Input> {
+        RiskMap::from_iter(lines)
+    }
+
+    fn part1(input: &Self::Input) -> anyhow::Result<Self::Part1> {
+        input.lowest_risk(false)
+    }
+
+    fn part2(input: &Self::Input) -> anyhow::Result<Self::Part2> {
+        input.expand(EXPANSION_FACTOR).lowest_risk(false)
+    }
+}
+
+#[derive(Copy, Clone, Eq, PartialEq)]
+struct Risk {
+    risk: ModNumC<u128, 9>
+}
+
+impl Risk {
+    fn from(risk: ModNumC<u8, 10>) -> Self {
+        Risk {risk: ModNumC::new(risk.a() as u128 - 1)}
+    }
+
+    fn risk(&self) -> u128 {1 + self.risk.a()}
+
+    fn bumped(&self) -> Self {Risk {risk: self.risk + 1}}
+}
+
+#[derive(Clone)]
+pub struct RiskMap {
+    risks: HashMap<Position, Risk>,
+    width: usize,
+    height: usize
+}
+
+impl RiskMap {
+    pub fn new(filename: &str) -> anyhow::Result<Self> {
+        Self::from_iter(input_lines(filename)?)
+    }
+
+    pub fn from_iter<I: Iterator<Item=String>>(lines: I) -> anyhow::Result<Self> {
+        let grid = digit_grid(lines)?;
+        let zero = grid.position_value_iter()
+            .filter(|(_, r)| r.a() == 0)
+            .map(|(p, _)| (p.row, p.col))
+            .min();
+        if let Some((row, col)) = zero {
+            return Err(ParseError::expected("a risk from 1 to 9", "0").at_column(col as usize + 1).at_line(row as usize + 1).into());
+        }
+        Ok(Self::from(grid.position_value_iter()
+            .map(|(p,r)| (*p, Risk::from(*r)))
+            .collect()))
+    }
+
+    fn from(risks: HashMap<Position, Risk>) -> Self {
+        let (width, height) = map_width_height(&risks);
+        RiskMap {risks, width, height}
+    }
+
+    pub fn expand(&self, expansion_factor: usize) -> Self {
+        let mut expanded_risks = self.risks.clone();
+        for offset in RowMajorPositionIterator::new(expansion_factor, expansion_factor).skip(1) {
+            let prev_dir = if offset.col == 0 {ManhattanDir::N} else {ManhattanDir::W};
+            let prev_offset = prev_dir.next_position(offset);
+            let prev_points = self.points_at(&prev_offset);
+            for (old_point, new_point) in prev_points.zip(self.points_at(&offset)) {
+                expanded_risks.insert(new_point, expanded_risks.get(&old_point).unwrap().bumped());
+            }
+        }
+        RiskMap::from(expanded_risks)
+    }
+
+    fn risk(&self, p: Position) -> Option<u128> {
+        self.risks.get(&p).map(|r| r.risk())
+    }
+
+    pub fn lowest_risk(&self, use_a_star: bool) -> anyhow::Result<u128> {
+        self.path_cost(use_a_star).cost().ok_or(anyhow!("No path to the goal"))
+    }
+
+    pub fn path_cost(&self, use_a_star: bool) -> SearchResult<AStarQueue<u128,Position>> {
+        let goal = Position::from(((self.width - 1) as isize, (self.height - 1) as isize));
+        let a_star_goal = if use_a_star {Some(goal)} else {None};
+        let start_node = a_star_node_from(Position::new(), 0, a_star_goal);
+        best_first_search(&start_node, |node, queue| {
+            if *node.item() == goal {
+                ContinueSearch::No
+            } else {
+                for neighbor in node.item().manhattan_neighbors() {
+                    if let Some(risk) = self.risk(neighbor) {
+                        let neighbor_node = a_star_node_from(neighbor, node.cost_so_far() + risk, a_star_goal);
+                        queue.enqueue(&neighbor_node);
+                    }
+                }
+                ContinueSearch::Yes
+            }
+        })
+    }
+
+    fn points_at<'a>(&'a self, offset: &'a Position) -> impl Iterator<Item=Position> + 'a {
+        self.risks.iter().map(|(p, _)|
+            Position::from((p.col + offset.col * self.width as isize,
+                            p.row + offset.row * self.height as isize)))
+    }
+}
+
+fn a_star_node_from(p: Position, cost_so_far: u128, g: Option<Position>) -> AStarNode<u128, Position> {
+    AStarNode::new(p, AStarCost::new(cost_so_far, g.map_or(0, |g| g.manhattan_distance(p) as u128)))
+}
+
+impl Display for RiskMap {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for p in RowMajorPositionIterator::new(self.width, self.height) {
+            if p.col == 0 && p.row > 0 {writeln!(f)?;}
+            write!(f, "{}", self.risks.get(&p).unwrap().risk())?
+        }
+        Ok(())
+    }
+}
+
+pub struct PathMap {
+    map: RiskMap,
+    path: HashSet<Position>
+}
+
+impl PathMap {
+    pub fn new(map: &RiskMap, path: VecDeque<Position>) -> Self {
+        //let enabled = ansi_term::enable_ansi_support(); // Maybe need this on Windows?
+        PathMap {map: map.clone(), path: path.iter().copied().collect()}
+    }
+}
+
+impl Display for PathMap {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for p in RowMajorPositionIterator::new(self.map.width, self.map.height) {
+            if p.col == 0 && p.row > 0 {writeln!(f)?;}
+            let risk = self.map.risks.get(&p).unwrap().risk();
+            if self.path.contains(&p) {
+                write!(f, "{}", ansi_term::Colour::Red.bold().paint(format!("{}", risk)))?;
+            } else {
+                write!(f, "{}", risk)?
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_errors() {
+        let map = |text: &str| RiskMap::from_iter(text.lines().map(|line| line.to_string()));
+        assert_eq!(map("19\n91").unwrap().risks.len(), 4);
+        assert_eq!(map("19\n9x").err().unwrap().to_string(), "2:2: expected a digit, found 'x'");
+        assert_eq!(map("19\n90").err().unwrap().to_string(), "2:2: expected a risk from 1 to 9, found '0'");
+    }
+}
@@ -0,0 +1,233 @@
+use std::str::Chars;
+use bits::BitArray;
+use num::{BigUint, One, Zero};
+use crate::Solver;
+use crate::parse_error::{next_line, ParseError, ParseResult};
+
+const VERSION_LENGTH: usize = 3;
+const OP_TYPE_LENGTH: usize = 3;
+const LITERAL_GROUP_LENGTH: usize = 4;
+const SUB_PACKETS_LENGTH: usize = 15;
+const SUB_PACKETS_COUNT: usize = 11;
+
+pub struct Day16Alt;
+
+impl Solver for Day16Alt {
+    type Input = String;
+    type Part1 = BigUint;
+    type Part2 = BigUint;
+
+    fn parse_lines<I: Iterator<Item=String>>(mut lines: I) -> anyhow::Result<Self::Input> {
+        let line = next_line(&mut lines, "a hexadecimal transmission")?;
+        version_and_calcluation(line.as_str()).map_err(|e| e.at_line(1))?;
+        Ok(line)
+    }
+
+    fn part1(input: &Self::Input) -> anyhow::Result<Self::Part1> {
+        Ok(version_and_calcluation(input.as_str())?.0)
+    }
+
+    fn part2(input: &Self::Input) -> anyhow::Result<Self::Part2> {
+        Ok(version_and_calcluation(input.as_str())?.1)
+    }
+}
+
+pub fn version_and_calcluation(line: &str) -> ParseResult<(BigUint, BigUint)> {
+    let binarized = hex2binary(line)?;
+    let mut bits = binarized.chars();
+    let (version_sum, calculation, _count) = located(parse_next_packet(&mut bits), binarized.as_str(), &bits)?;
+    Ok((version_sum, calculation))
+}
+
+fn parse_next_packet(iter: &mut Chars) -> ParseResult<(BigUint, BigUint, usize)> {
+    let version = bits2bigint(iter, VERSION_LENGTH)?;
+    let op_type = bits2string(iter, OP_TYPE_LENGTH)?;
+    let (version_sum, calculation, count) = match op_type.chars().next() {
+        Some('0') => parse_zero_operator(op_type.as_str(), iter)?,
+        _ => parse_one_operator(op_type.as_str(), iter)?
+    };
+    Ok((version + version_sum, calculation, count + VERSION_LENGTH + OP_TYPE_LENGTH))
+}
+
+fn parse_zero_operator(op_type: &str, iter: &mut Chars) -> ParseResult<(BigUint, BigUint, usize)> {
+    let (version_sum, sub_packets, count) = parse_sub_packets(iter)?;
+    if sub_packets.is_empty() {
+        return Err(ParseError::expected("at least one sub-packet", "none"));
+    }
+    let calculation = match op_type {
+        "000" => sub_packets.iter().sum::<BigUint>(),
+        "001" => sub_packets.iter().product::<BigUint>(),
+        "010" => sub_packets.iter().min().unwrap().clone(),
+        "011" => sub_packets.iter().max().unwrap().clone(),
+        other => return Err(ParseError::expected("an operator type", other))
+    };
+    Ok((version_sum, calculation.clone(), count))
+}
+
+fn parse_one_operator(op_type: &str, iter: &mut Chars) -> ParseResult<(BigUint, BigUint, usize)> {
+    if op_type == "100" {
+        let (literal, count) = parse_literal(iter)?;
+        Ok((BigUint::zero(), literal, count))
+    } else {
+        let (version_sum, sub_packets, count) = parse_sub_packets(iter)?;
+        if sub_packets.len() != 2 {
+            return Err(ParseError::expected("two sub-packets", sub_packets.len().to_string().as_str()));
+        }
+        let op = match op_type {
+            "101" => sub_packets[0] > sub_packets[1],
+            "110" => sub_packets[0] < sub_packets[1],
+            "111" => sub_packets[0] == sub_packets[1],
+            other => return Err(ParseError::expected("an operator type", other))
+        };
+        let value = if op {BigUint::one()} else {BigUint::zero()};
+        Ok((version_sum, value, count))
+    }
+}
+
+fn parse_literal(iter: &mut Chars) -> ParseResult<(BigUint, usize)> {
+    let mut actual_bits = String::new();
+    let mut bits_used = 0;
+    loop {
+        let header = iter.next().ok_or(ParseError::end_of_input("a literal group"))?;
+        bits_used += 1;
+        actual_bits.push_str(bits2string(iter, LITERAL_GROUP_LENGTH)?.as_str());
+        bits_used += LITERAL_GROUP_LENGTH;
+        if header == '0' {break;}
+    }
+    Ok((BigUint::from(&actual_bits.parse::<BitArray>()?), bits_used))
+}
+
+fn parse_sub_packets(iter: &mut Chars) -> ParseResult<(BigUint, Vec<BigUint>, usize)> {
+    let mut packets = Vec::new();
+    let mut version_sum = BigUint::zero();
+    let mut bits_used = 1;
+    match iter.next().ok_or(ParseError::end_of_input("a length type"))? {
+        '0' => parse_sub_0(iter, &mut bits_used, &mut version_sum, &mut packets)?,
+        '1' => parse_sub_1(iter, &mut bits_used, &mut version_sum, &mut packets)?,
+        other => return Err(ParseError::expected("a length type", other.to_string().as_str()))
+    }
+    Ok((version_sum, packets, bits_used))
+}
+
+fn parse_sub_0(iter: &mut Chars, bits_used: &mut usize, version_sum: &mut BigUint, packets: &mut Vec<BigUint>) -> ParseResult<()> {
+    let mut length = bits2bigint(iter, SUB_PACKETS_LENGTH)?;
+    *bits_used += SUB_PACKETS_LENGTH;
+    while length > BigUint::zero() {
+        let (version, packet, used) = parse_next_packet(iter)?;
+        length -= BigUint::from(used);
+        *bits_used += used;
+        *version_sum += version;
+        packets.push(packet);
+    }
+    Ok(())
+}
+
+fn parse_sub_1(iter: &mut Chars, bits_used: &mut usize, version_sum: &mut BigUint, packets: &mut Vec<BigUint>) -> ParseResult<()> {
+    let count = bits2bigint(iter, SUB_PACKETS_COUNT)?;
+    *bits_used += SUB_PACKETS_COUNT;
+    for _ in num::range(BigUint::zero(), count) {
+        let (version, packet, used) = parse_next_packet(iter)?;
+        *bits_used += used;
+        *version_sum += version;
+        packets.push(packet);
+    }
+    Ok(())
+}
+
+fn bits2string(iter: &mut Chars, bits_to_take: usize) -> ParseResult<String> {
+    let bits = iter.take(bits_to_take).collect::<String>();
+    if bits.len() < bits_to_take {
+        return Err(ParseError::end_of_input(format!("{} more bits", bits_to_take - bits.len()).as_str()));
+    }
+    Ok(bits)
+}
+
+fn bits2bits(iter: &mut Chars, bits_to_take: usize) -> ParseResult<BitArray> {
+    Ok(bits2string(iter, bits_to_take)?.parse()?)
+}
+
+fn bits2bigint(iter: &mut Chars, bits_to_take: usize) -> ParseResult<BigUint> {
+    Ok(BigUint::from(&bits2bits(iter, bits_to_take)?))
+}
+
+fn hex2binary(hex: &str) -> ParseResult<String> {
+    hex.chars().enumerate()
+        .map(|(i, c)| char_matcher(c).map_err(|e| e.at_column(i + 1)))
+        .collect()
+}
+
+/// Locates errors from parsing the packet in `binary` at the hex digit holding the next unread bit.
+fn located<T>(result: ParseResult<T>, binary: &str, unread: &Chars) -> ParseResult<T> {
+    result.map_err(|e| e.at_column((binary.len() - unread.as_str().len()) / 4 + 1))
+}
+
+fn char_matcher(c: char) -> ParseResult<String> {
+    Ok(match c {
+        '0' => "0000",
+        '1' => "0001",
+        '2' => "0010",
+        '3' => "0011",
+        '4' => "0100",
+        '5' => "0101",
+        '6' => "0110",
+        '7' => "0111",
+        '8' => "1000",
+        '9' => "1001",
+        'A' => "1010",
+        'B' => "1011",
+        'C' => "1100",
+        'D' => "1101",
+        'E' => "1110",
+        'F' => "1111",
+        other => { return Err(ParseError::expected("a hexadecimal digit", other.to_string().as_str())); }
+    }.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_errors() {
+        let error = Day16Alt::parse_str("D2XE28").unwrap_err();
+        assert_eq!(error.to_string(), "1:3: expected a hexadecimal digit, found 'X'");
+        let error = Day16Alt::parse_str("D2FE").unwrap_err();
+        assert_eq!(error.to_string(), "1:5: expected a literal group, found end of input");
+        assert!(Day16Alt::parse_str("").is_err());
+    }
+
+    #[test]
+    fn test_part_1() {
+        for (hex, value) in [
+            ("D2FE28", 6),
+            ("38006F45291200", 1 + 6 + 2),
+            ("EE00D40C823060", 7 + 2 + 4 + 1),
+            ("8A004A801A8002F478", 16),
+            ("620080001611562C8802118E34", 12),
+            ("C0015000016115A2E0802F182340", 23),
+            ("A0016C880162017C3686B18A3D4780", 31)
+        ] {
+            println!("Hex: {} (sum: {})", hex, value);
+            let (version_sum, _calculation) = version_and_calcluation(hex).unwrap();
+            assert_eq!(version_sum, BigUint::from(value as usize));
+        }
+    }
+
+    #[test]
+    fn test_part_2() {
+        for (hex, value) in [
+            ("C200B40A82", 3),
+            ("04005AC33890", 54),
+            ("880086C3E88112", 7),
+            ("CE00C43D881120", 9),
+            ("D8005AC2A8F0", 1),
+            ("F600BC2D8F", 0),
+            ("9C005AC2F8F0", 0),
+            ("9C0141080250320F1802104A08", 1),
+        ] {
+            println!("Hex: {} (sum: {})", hex, value);
+            let (_version_sum, calculation) = version_and_calcluation(hex).unwrap();
+            assert_eq!(calculation, BigUint::from(value as usize));
+        }
+    }
+}
\ No newline at end of file
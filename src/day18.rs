@@ -0,0 +1,361 @@
+use std::fmt::{Display, Formatter};
+use std::ops::Add;
+use std::str::FromStr;
+use crate::Solver;
+use crate::parse_error::{parse_each, ParseError, ParseResult};
+
+pub struct Day18;
+
+impl Solver for Day18 {
+    type Input = Vec<SailfishNumber>;
+    type Part1 = u32;
+    type Part2 = u32;
+
+    fn parse_lines<I: Iterator<Item=String>>(lines: I) -> anyhow::Result<Self::Input> {
+        let numbers = parse_each(lines)?;
+        if numbers.is_empty() {
+            return Err(ParseError::end_of_input("a snailfish number").into());
+        }
+        Ok(numbers)
+    }
+
+    fn part1(input: &Self::Input) -> anyhow::Result<Self::Part1> {
+        Ok(part1(input))
+    }
+
+    fn part2(input: &Self::Input) -> anyhow::Result<Self::Part2> {
+        Ok(part2(input))
+    }
+}
+
+pub fn part1(nums: &Vec<SailfishNumber>) -> u32 {
+    nums.iter()
+        .cloned()
+        .reduce(|a, b| &a + &b)
+        .unwrap()
+        .magnitude()
+}
+
+pub fn part2(nums: &Vec<SailfishNumber>) -> u32 {
+    let mut max = 0;
+    for n1 in nums.iter() {
+        for n2 in nums.iter() {
+            if n1 != n2 {
+                let magnitude = (n1 + n2).magnitude();
+                if magnitude > max {
+                    max = magnitude;
+                }
+            }
+        }
+    }
+    max
+}
+
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub enum SailfishNumber {
+    Num(u32),
+    Pair(Box<SailfishNumber>, Box<SailfishNumber>)
+}
+
+impl SailfishNumber {
+    pub fn magnitude(&self) -> u32 {
+        match self {
+            SailfishNumber::Num(n) => *n,
+            SailfishNumber::Pair(sn1, sn2) => 3 * sn1.magnitude() + 2 * sn2.magnitude()
+        }
+    }
+
+    fn split(&self) -> (SailfishNumber, bool) {
+        self.split_leftmost()
+    }
+
+    fn split_leftmost(&self) -> (SailfishNumber, bool) {
+        match self {
+            SailfishNumber::Num(num) => {
+                if *num > 9 {
+                    let low_half = num / 2;
+                    let high_half = low_half + num % 2;
+                    (SailfishNumber::Pair(Box::new(SailfishNumber::Num(low_half)), Box::new(SailfishNumber::Num(high_half))), true)
+                } else {
+                    (self.clone(), false)
+                }
+            }
+            SailfishNumber::Pair(left, right) => {
+                let (updated_left, did_split) = left.split_leftmost();
+                if did_split {
+                    (SailfishNumber::Pair(Box::new(updated_left), right.clone()), true)
+                } else {
+                    let (updated_right, did_split) = right.split_leftmost();
+                    (SailfishNumber::Pair(Box::new(updated_left), Box::new(updated_right)), did_split)
+                }
+            }
+        }
+    }
+
+    fn reduced(&self) -> Self {
+        let mut result = self.clone();
+        loop {
+            let (exploded, explode_needed, _, _) = result.exploded(0);
+            if explode_needed {
+                result = exploded;
+            } else {
+                let (split, split_needed) = exploded.split();
+                result = split;
+                if !split_needed {
+                    return result;
+                }
+            }
+        }
+    }
+
+    fn unwrap_num(&self) -> u32 {
+        match self {
+            SailfishNumber::Num(num) => *num,
+            _ => panic!("This should never happen!")
+        }
+    }
+
+    fn is_num(&self) -> bool {
+        match self {
+            SailfishNumber::Num(_) => true,
+            SailfishNumber::Pair(_, _) => false
+        }
+    }
+
+    fn explodable(&self) -> bool {
+        match self {
+            SailfishNumber::Num(_) => false,
+            SailfishNumber::Pair(a, b) => {
+                a.is_num() && b.is_num()
+            }
+        }
+    }
+
+    fn exploded(&self, depth: usize) -> (SailfishNumber, bool, Option<u32>, Option<u32>) {
+        match self {
+            SailfishNumber::Num(_) => (self.clone(), false, None, None),
+            SailfishNumber::Pair(sn1, sn2) => {
+                if depth >= 4 && self.explodable() {
+                    (SailfishNumber::Num(0), true, Some(sn1.unwrap_num()), Some(sn2.unwrap_num()))
+                } else {
+                    let (left, did_explode, left_num, right_num) = sn1.exploded(depth + 1);
+                    if did_explode {
+                        let right = if let Some(right_num) = right_num {
+                            Box::new(sn2.leftmost_added(right_num))
+                        } else {
+                            sn2.clone()
+                        };
+                        (SailfishNumber::Pair(Box::new(left), right), true, left_num, None)
+                    } else {
+                        let (right, did_explode, left_num, right_num) = sn2.exploded(depth + 1);
+                        if did_explode {
+                            let thing = if let Some(left_num) = left_num {
+                                Box::new(sn1.rightmost_added(left_num))
+                            } else {
+                                sn1.clone()
+                            };
+                            (SailfishNumber::Pair(thing, Box::new(right)), true, None, right_num)
+                        } else {
+                            (self.clone(), false, None, None)
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    fn rightmost_added(&self, value: u32) -> Self {
+        match self {
+            SailfishNumber::Num(num) => SailfishNumber::Num(*num + value),
+            SailfishNumber::Pair(left, right) =>
+                SailfishNumber::Pair(left.clone(), Box::new(right.rightmost_added(value)))
+        }
+    }
+
+    fn leftmost_added(&self, value: u32) -> Self {
+        match self {
+            SailfishNumber::Num(num) => SailfishNumber::Num(*num + value),
+            SailfishNumber::Pair(left, right) =>
+                SailfishNumber::Pair(Box::new(left.leftmost_added(value)), right.clone())
+        }
+    }
+}
+
+impl Add for &SailfishNumber {
+    type Output = SailfishNumber;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        SailfishNumber::Pair(Box::new(self.clone()), Box::new(rhs.clone())).reduced()
+    }
+}
+
+impl FromStr for SailfishNumber {
+    type Err = ParseError;
+
+    /// Whitespace between the tokens is ignored.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let end = s.chars().count() + 1;
+        let mut chars = s.chars().enumerate().filter(|(_, c)| !c.is_whitespace());
+        let number = parser(&mut chars).map_err(|e| if e.column.is_none() {e.at_column(end)} else {e})?;
+        match chars.next() {
+            None => Ok(number),
+            Some((i, c)) => Err(ParseError::expected("end of line", c.to_string().as_str()).at_column(i + 1))
+        }
+    }
+}
+
+fn parser<I: Iterator<Item=(usize, char)>>(chars: &mut I) -> ParseResult<SailfishNumber> {
+    match chars.next() {
+        Some((_, c)) if c.is_ascii_digit() => Ok(SailfishNumber::Num(c.to_digit(10).unwrap())),
+        Some((_, '[')) => {
+            let left = Box::new(parser(chars)?);
+            expect(chars, ',')?;
+            let right = Box::new(parser(chars)?);
+            expect(chars, ']')?;
+            Ok(SailfishNumber::Pair(left, right))
+        }
+        Some((i, c)) => Err(ParseError::expected("a digit or '['", c.to_string().as_str()).at_column(i + 1)),
+        None => Err(ParseError::end_of_input("a digit or '['"))
+    }
+}
+
+fn expect<I: Iterator<Item=(usize, char)>>(chars: &mut I, token: char) -> ParseResult<()> {
+    let expected = format!("'{}'", token);
+    match chars.next() {
+        Some((_, c)) if c == token => Ok(()),
+        Some((i, c)) => Err(ParseError::expected(expected.as_str(), c.to_string().as_str()).at_column(i + 1)),
+        None => Err(ParseError::end_of_input(expected.as_str()))
+    }
+}
+
+impl Display for SailfishNumber {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SailfishNumber::Num(num) => {write!(f, "{}", num)}
+            SailfishNumber::Pair(sn1, sn2) => {
+                write!(f, "[")?;
+                write!(f, "{}", sn1)?;
+                write!(f, ",")?;
+                write!(f, "{}", sn2)?;
+                write!(f, "]")
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use advent_code_lib::all_lines;
+    use quickcheck::{Arbitrary, Gen};
+    use quickcheck_macros::quickcheck;
+    use super::*;
+
+    const MAX_REDUCED_DEPTH: usize = 4;
+
+    // Generates only reduced numbers, since those are the only ones the puzzle ever adds.
+    impl Arbitrary for SailfishNumber {
+        fn arbitrary(g: &mut Gen) -> Self {
+            arbitrary_at(g, 0)
+        }
+    }
+
+    fn arbitrary_at(g: &mut Gen, depth: usize) -> SailfishNumber {
+        if depth < MAX_REDUCED_DEPTH && bool::arbitrary(g) {
+            SailfishNumber::Pair(Box::new(arbitrary_at(g, depth + 1)), Box::new(arbitrary_at(g, depth + 1)))
+        } else {
+            SailfishNumber::Num(u32::arbitrary(g) % 10)
+        }
+    }
+
+    fn is_reduced(num: &SailfishNumber, depth: usize) -> bool {
+        match num {
+            SailfishNumber::Num(n) => *n <= 9,
+            SailfishNumber::Pair(a, b) =>
+                depth < MAX_REDUCED_DEPTH && is_reduced(a, depth + 1) && is_reduced(b, depth + 1)
+        }
+    }
+
+    #[quickcheck]
+    fn prop_sum_is_reduced(a: SailfishNumber, b: SailfishNumber) -> bool {
+        is_reduced(&(&a + &b), 0)
+    }
+
+    #[quickcheck]
+    fn prop_display_parses_back(a: SailfishNumber) -> bool {
+        format!("{}", a).parse::<SailfishNumber>().unwrap() == a
+    }
+
+    #[test]
+    fn parse_test() {
+        for line in all_lines("ex/day18_2.txt").unwrap() {
+            let parsed: SailfishNumber = line.parse().unwrap();
+            let unparsed = format!("{}", parsed);
+            assert_eq!(unparsed, line);
+        }
+    }
+
+    #[test]
+    fn parse_error_test() {
+        let error = "[1,2".parse::<SailfishNumber>().unwrap_err();
+        assert_eq!(error.to_string(), "5: expected ']', found end of input");
+        let error = "[1;2]".parse::<SailfishNumber>().unwrap_err();
+        assert_eq!(error.to_string(), "3: expected ',', found ';'");
+        let error = "[1,x]".parse::<SailfishNumber>().unwrap_err();
+        assert_eq!(error.to_string(), "4: expected a digit or '[', found 'x'");
+        let error = "[1,2]]".parse::<SailfishNumber>().unwrap_err();
+        assert_eq!(error.to_string(), "6: expected end of line, found ']'");
+        let error = Day18::parse_str("[1,2]\n[3,4").unwrap_err();
+        assert_eq!(error.to_string(), "2:5: expected ']', found end of input");
+        assert!(Day18::parse_str("").is_err());
+    }
+
+    #[test]
+    fn expand_test() {
+        for (before, after) in [
+            ("[[[[[9,8],1],2],3],4]", "[[[[0,9],2],3],4]"),
+            ("[7,[6,[5,[4,[3,2]]]]]", "[7,[6,[5,[7,0]]]]"),
+            ("[[6,[5,[4,[3,2]]]],1]", "[[6,[5,[7,0]]],3]"),
+            ("[[3,[2,[1,[7,3]]]],[6,[5,[4,[3,2]]]]]", "[[3,[2,[8,0]]],[9,[5,[4,[3,2]]]]]"),
+            ("[[3,[2,[8,0]]],[9,[5,[4,[3,2]]]]]", "[[3,[2,[8,0]]],[9,[5,[7,0]]]]")
+        ] {
+            let start: SailfishNumber = before.parse().unwrap();
+            let exploded = start.exploded(0);
+            let end = format!("{}", exploded.0);
+            assert_eq!(end.as_str(), after);
+        }
+    }
+
+    #[test]
+    fn add_test_1() {
+        let one: SailfishNumber = "[[[[4,3],4],4],[7,[[8,4],9]]]".parse().unwrap();
+        let two: SailfishNumber = "[1,1]".parse().unwrap();
+        let sum = &one + &two;
+        let sum_str = format!("{}", sum);
+        assert_eq!(sum_str.as_str(), "[[[[0,7],4],[[7,8],[6,0]]],[8,1]]");
+    }
+
+    #[test]
+    fn add_test_2() {
+        let mut sum: SailfishNumber = "[1,1]".parse().unwrap();
+        for i in 2..=6 {
+            sum = &sum + &format!("[{},{}]", i, i).parse::<SailfishNumber>().unwrap();
+            println!("{}: {}", i, sum);
+        }
+        let result: SailfishNumber = "[[[[5,0],[7,4]],[5,5]],[6,6]]".parse().unwrap();
+        assert_eq!(sum, result);
+    }
+
+    #[test]
+    fn add_test_3() {
+        let one: SailfishNumber = "[[[0,[4,5]],[0,0]],[[[4,5],[2,6]],[9,5]]]".parse().unwrap();
+        let two: SailfishNumber = "[7,[[[3,7],[4,3]],[[6,3],[8,8]]]]".parse().unwrap();
+        let sum = &one + &two;
+        let sum_str = format!("{}", sum);
+        assert_eq!(sum_str.as_str(), "[[[[4,0],[5,4]],[[7,7],[6,0]]],[[8,[7,7]],[[7,9],[5,0]]]]");
+    }
+
+    #[test]
+    fn test_examples() {
+        crate::answers::assert_examples(18);
+    }
+}
\ No newline at end of file
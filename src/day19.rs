@@ -0,0 +1,476 @@
+use std::collections::{HashMap, HashSet};
+use std::fmt::{Display, Formatter};
+use std::ops::{Add, Mul, Neg, Sub};
+use std::str::FromStr;
+use advent_code_lib::ExNihilo;
+use anyhow::bail;
+use bare_metal_modulo::{MNum, ModNumC};
+use crate::{input_lines, Solver};
+use crate::parse_error::{numbers_in, ParseError, ParseResult};
+
+const MIN_OVERLAPPING_POINTS: usize = 12;
+
+pub struct Day19;
+
+impl Solver for Day19 {
+    type Input = Scanners;
+    type Part1 = usize;
+    type Part2 = isize;
+
+    fn parse_lines<I: Iterator<Item=String>>(lines: I) -> anyhow::Result<Self::Input> {
+        Ok(Scanners::from_iter(lines)?)
+    }
+
+    fn part1(input: &Self::Input) -> anyhow::Result<Self::Part1> {
+        Ok(input.align()?.beacons.len())
+    }
+
+    fn part2(input: &Self::Input) -> anyhow::Result<Self::Part2> {
+        Ok(input.align()?.largest_distance())
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Scanners {
+    scanners: Vec<Scanner>
+}
+
+/// Every beacon and scanner position, relative to the first scanner.
+#[derive(Clone, Debug)]
+pub struct Alignment {
+    pub beacons: HashSet<Point3>,
+    pub scanners: Vec<Point3>
+}
+
+impl Alignment {
+    pub fn largest_distance(&self) -> isize {
+        self.scanners.iter()
+            .flat_map(|a| self.scanners.iter().map(|b| a.manhattan_distance(*b)))
+            .max()
+            .unwrap_or(0)
+    }
+}
+
+impl Scanners {
+    pub fn from_file(filename: &str) -> ParseResult<Self> {
+        Self::from_iter(input_lines(filename)?).map_err(|e| e.in_file(filename))
+    }
+
+    /// Scanners are separated by blank lines, and their `--- scanner N ---` headers are skipped.
+    pub fn from_iter<I: Iterator<Item=String>>(lines: I) -> ParseResult<Self> {
+        let mut scanners = vec![Scanner::create()];
+        for (i, line) in lines.enumerate() {
+            if line.trim().is_empty() {
+                scanners.push(Scanner::create());
+            } else if !line.contains("scanner") {
+                let beacon = line.parse().map_err(|e: ParseError| e.at_line(i + 1))?;
+                scanners.last_mut().unwrap().add_beacon(beacon);
+            }
+        }
+        Ok(Scanners {scanners})
+    }
+
+    /// Places each scanner by matching it against the scanners already placed, starting from the first.
+    pub fn align(&self) -> anyhow::Result<Alignment> {
+        let mut placed: Vec<Option<(Point3, Vec<Point3>)>> = vec![None; self.scanners.len()];
+        placed[0] = Some((Point3 {x: 0, y: 0, z: 0}, self.scanners[0].beacons.clone()));
+        let mut pending = vec![0];
+        while let Some(i) = pending.pop() {
+            let known = placed[i].as_ref().unwrap().1.clone();
+            for (j, scanner) in self.scanners.iter().enumerate() {
+                if placed[j].is_none() && self.scanners[i].may_overlap(scanner) {
+                    if let Some(found) = scanner.placed_against(&known) {
+                        placed[j] = Some(found);
+                        pending.push(j);
+                    }
+                }
+            }
+        }
+        let mut alignment = Alignment {beacons: HashSet::new(), scanners: Vec::new()};
+        for (i, scanner) in placed.into_iter().enumerate() {
+            match scanner {
+                Some((position, beacons)) => {
+                    alignment.scanners.push(position);
+                    alignment.beacons.extend(beacons);
+                }
+                None => bail!("Scanner {} shares no {} beacons with the others", i, MIN_OVERLAPPING_POINTS)
+            }
+        }
+        Ok(alignment)
+    }
+}
+
+impl Display for Scanners {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for (i, scanner) in self.scanners.iter().enumerate() {
+            writeln!(f, "--- scanner {} ---", i)?;
+            for beacon in scanner.beacons.iter() {
+                writeln!(f, "{}", beacon)?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+struct Scanner {
+    beacons: Vec<Point3>,
+    /// How many pairs of beacons are each squared distance apart, which no rotation or offset changes.
+    distances: HashMap<isize, usize>
+}
+
+impl Scanner {
+    fn add_beacon(&mut self, beacon: Point3) {
+        for other in self.beacons.iter() {
+            let d = *other - beacon;
+            *self.distances.entry(d.x * d.x + d.y * d.y + d.z * d.z).or_insert(0) += 1;
+        }
+        self.beacons.push(beacon);
+    }
+
+    /// Scanners sharing 12 beacons share the distances between every pair of them.
+    fn may_overlap(&self, other: &Scanner) -> bool {
+        let shared = self.distances.iter()
+            .map(|(d, count)| other.distances.get(d).map_or(0, |c| *c.min(count)))
+            .sum::<usize>();
+        shared >= MIN_OVERLAPPING_POINTS * (MIN_OVERLAPPING_POINTS - 1) / 2
+    }
+
+    /// This scanner's position and beacons, if some rotation and offset lines up at least 12 of its
+    /// beacons with `known`.
+    fn placed_against(&self, known: &[Point3]) -> Option<(Point3, Vec<Point3>)> {
+        for (_, transform) in ROTATION_PROBE.transforms() {
+            let rotated = self.beacons.iter().map(|b| transform.transformed(*b)).collect::<Vec<_>>();
+            let mut offsets = HashMap::new();
+            for k in known.iter() {
+                for r in rotated.iter() {
+                    let offset = *k - *r;
+                    let count = offsets.entry(offset).or_insert(0);
+                    *count += 1;
+                    if *count >= MIN_OVERLAPPING_POINTS {
+                        return Some((offset, rotated.iter().map(|r| *r + offset).collect()));
+                    }
+                }
+            }
+        }
+        None
+    }
+}
+
+impl ExNihilo for Scanner {
+    fn create() -> Self {Scanner {beacons: Vec::new(), distances: HashMap::new()}}
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct Point3 {
+    pub x: isize, pub y: isize, pub z: isize
+}
+
+impl Point3 {
+    pub fn manhattan_distance(&self, other: Point3) -> isize {
+        let diff = (*self - other).abs();
+        diff.x + diff.y + diff.z
+    }
+
+    /// True if `other` is at most `range` away from this point along every axis.
+    pub fn within(&self, other: Point3, range: isize) -> bool {
+        let diff = (*self - other).abs();
+        diff.x <= range && diff.y <= range && diff.z <= range
+    }
+
+    /// This point as seen from each of the 24 orientations a scanner might have.
+    pub fn orientations(&self) -> Vec<Point3> {
+        self.transforms().map(|(p, _)| p).collect()
+    }
+
+    fn abs(&self) -> Self {
+        Point3 {x: self.x.abs(), y: self.y.abs(), z: self.z.abs()}
+    }
+
+    #[cfg(test)]
+    fn signum(&self) -> Self {
+        Point3 {x: self.x.signum(), y: self.y.signum(), z: self.z.signum()}
+    }
+
+    fn rotated90(&self) -> Self {
+        Point3 {x: self.x, y: -self.z, z: self.y}
+    }
+
+    fn flipped(&self) -> Self {
+        Point3 {x: -self.x, y: -self.y, z: self.z}
+    }
+
+    fn advanced(&self) -> Self {
+        Point3 {x: self.y, y: self.z, z: self.x}
+    }
+
+    fn transforms(&self) -> TransformIterator {
+        TransformIterator {transform: Transform::new(), point: *self, done: false}
+    }
+}
+
+impl FromStr for Point3 {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match numbers_in(s, ',')?.as_slice() {
+            [x, y, z] => Ok(Point3 {x: *x, y: *y, z: *z}),
+            _ => Err(ParseError::expected("x,y,z", s))
+        }
+    }
+}
+
+impl Add for Point3 {
+    type Output = Point3;
+    fn add(self, rhs: Self) -> Self::Output {
+        Point3 {x: self.x + rhs.x, y: self.y + rhs.y, z: self.z + rhs.z}
+    }
+}
+
+impl Neg for Point3 {
+    type Output = Point3;
+    fn neg(self) -> Self::Output {Point3 {x: -self.x, y: -self.y, z: -self.z}}
+}
+
+impl Sub for Point3 {
+    type Output = Point3;
+    fn sub(self, rhs: Self) -> Self::Output {self + (-rhs)}
+}
+
+// Hadamard Product
+impl Mul for Point3 {
+    type Output = Point3;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        Point3 {x: self.x * rhs.x, y: self.y * rhs.y, z: self.z * rhs.z}
+    }
+}
+
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Hash)]
+struct Transform {
+    axes: ModNumC<u8, 3>,
+    flips: ModNumC<u8, 2>,
+    rotations90: ModNumC<u8, 4>
+}
+
+/// The rotation that applies `self` and then `rhs`.
+impl Add for Transform {
+    type Output = Transform;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Transform::rotating(ROTATION_PROBE, rhs.transformed(self.transformed(ROTATION_PROBE)))
+    }
+}
+
+/// The rotation that undoes `self`.
+impl Neg for Transform {
+    type Output = Transform;
+
+    fn neg(self) -> Self::Output {
+        Transform::rotating(self.transformed(ROTATION_PROBE), ROTATION_PROBE)
+    }
+}
+
+const ROTATION_PROBE: Point3 = Point3 {x: 1, y: 2, z: 3};
+
+impl Transform {
+    fn new() -> Self {
+        Transform {axes: ModNumC::new(0), flips: ModNumC::new(0), rotations90: ModNumC::new(0)}
+    }
+
+    /// Every rotation takes `ROTATION_PROBE` somewhere different, so this finds the only one taking `from` to `to`.
+    fn rotating(from: Point3, to: Point3) -> Self {
+        from.transforms().find(|(p, _)| *p == to).map(|(_, t)| t).unwrap()
+    }
+
+    fn transformed(&self, p: Point3) -> Point3 {
+        let mut result = p;
+        for _ in 0..self.axes.a() {result = result.advanced();}
+        for _ in 0..self.flips.a() {result = result.flipped();}
+        for _ in 0..self.rotations90.a() {result = result.rotated90();}
+        result
+    }
+}
+
+struct TransformIterator {
+    transform: Transform,
+    point: Point3,
+    done: bool
+}
+
+impl Iterator for TransformIterator {
+    type Item = (Point3, Transform);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            None
+        } else {
+            let result = (self.point, self.transform);
+            self.point = self.point.rotated90();
+            self.transform.rotations90 += 1;
+            if self.transform.rotations90 == 0 {
+                self.point = self.point.flipped();
+                self.transform.flips += 1;
+                if self.transform.flips == 0 {
+                    self.point = self.point.advanced();
+                    self.transform.axes += 1;
+                    self.done = self.transform.axes == 0;
+                }
+            }
+            Some(result)
+        }
+    }
+}
+
+impl Display for Point3 {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{},{},{}", self.x, self.y, self.z)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use quickcheck::{Arbitrary, Gen};
+    use quickcheck_macros::quickcheck;
+    use super::*;
+
+    impl Arbitrary for Transform {
+        fn arbitrary(g: &mut Gen) -> Self {
+            Transform {axes: ModNumC::new(u8::arbitrary(g)), flips: ModNumC::new(u8::arbitrary(g)), rotations90: ModNumC::new(u8::arbitrary(g))}
+        }
+    }
+
+    impl Arbitrary for Point3 {
+        fn arbitrary(g: &mut Gen) -> Self {
+            Point3 {x: i16::arbitrary(g) as isize, y: i16::arbitrary(g) as isize, z: i16::arbitrary(g) as isize}
+        }
+    }
+
+    fn all_transforms() -> Vec<Transform> {
+        Point3 {x: 1, y: 2, z: 3}.transforms().map(|(_, t)| t).collect()
+    }
+
+    fn determinant(t: Transform) -> isize {
+        let [a, b, c] = [(1, 0, 0), (0, 1, 0), (0, 0, 1)].map(|(x, y, z)| t.transformed(Point3 {x, y, z}));
+        a.x * (b.y * c.z - b.z * c.y) - a.y * (b.x * c.z - b.z * c.x) + a.z * (b.x * c.y - b.y * c.x)
+    }
+
+    #[quickcheck]
+    fn prop_transform_identity_and_inverse(t: Transform) -> bool {
+        t + Transform::new() == t && t + -t == Transform::new()
+    }
+
+    #[quickcheck]
+    fn prop_transform_associative(a: Transform, b: Transform, c: Transform) -> bool {
+        (a + b) + c == a + (b + c)
+    }
+
+    #[quickcheck]
+    fn prop_transform_closed(a: Transform, b: Transform) -> bool {
+        all_transforms().contains(&(a + b))
+    }
+
+    #[quickcheck]
+    fn prop_transform_composes(a: Transform, b: Transform, p: Point3) -> bool {
+        (a + b).transformed(p) == b.transformed(a.transformed(p)) && (-a).transformed(a.transformed(p)) == p
+    }
+
+    #[quickcheck]
+    fn prop_transform_preserves_magnitudes(t: Transform, p: Point3) -> bool {
+        let magnitudes = |p: Point3| {
+            let mut m = [p.x.abs(), p.y.abs(), p.z.abs()];
+            m.sort();
+            m
+        };
+        magnitudes(t.transformed(p)) == magnitudes(p)
+    }
+
+    #[test]
+    fn test_transforms_are_the_24_rotations() {
+        let transforms = all_transforms();
+        assert_eq!(transforms.len(), 24);
+        assert!(transforms.iter().all(|t| determinant(*t) == 1));
+        let start = Point3 {x: 1, y: 2, z: 3};
+        let images = transforms.iter().map(|t| t.transformed(start)).collect::<HashSet<_>>();
+        assert_eq!(images.len(), 24);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let error = "1,2".parse::<Point3>().unwrap_err();
+        assert_eq!(error.to_string(), "expected x,y,z, found '1,2'");
+        let error = Day19::parse_str("--- scanner 0 ---\n1,2,3\n\n--- scanner 1 ---\n4,x,6").unwrap_err();
+        assert_eq!(error.to_string(), "5:3: expected a number, found 'x'");
+    }
+
+    #[test]
+    fn test_example() {
+        let scanners = Scanners::from_file("ex/day19.txt").unwrap();
+        let alignment = scanners.align().unwrap();
+        assert_eq!(alignment.beacons.len(), 79);
+        assert_eq!(alignment.scanners[1], Point3 {x: 68, y: -1246, z: -43});
+        assert_eq!(alignment.largest_distance(), 3621);
+        let apart = Day19::parse_str("--- scanner 0 ---\n1,2,3\n\n--- scanner 1 ---\n4,5,6").unwrap();
+        assert_eq!(apart.align().unwrap_err().to_string(), "Scanner 1 shares no 12 beacons with the others");
+    }
+
+    #[test]
+    fn test_point_io() {
+        for point_str in ["-892,524,684", "-876,649,763"] {
+            let point: Point3 = point_str.parse().unwrap();
+            assert_eq!(point_str, format!("{}", point).as_str());
+        }
+    }
+
+    #[test]
+    fn test_transform_iterator() {
+        let start = Point3 {x: 1, y: 2, z: 3};
+        for ((point, transform), expected) in start.transforms()
+            .zip([
+                ( 1,  2,  3), ( 1, -3,  2), ( 1, -2, -3), ( 1,  3, -2),
+                (-1, -2,  3), (-1, -3, -2), (-1,  2, -3), (-1,  3,  2),
+                ( 2,  3,  1), ( 2, -1,  3), ( 2, -3, -1), ( 2,  1, -3),
+                (-2, -3,  1), (-2, -1, -3), (-2,  3, -1), (-2,  1,  3),
+                ( 3,  1,  2), ( 3, -2,  1), ( 3, -1, -2), ( 3,  2, -1),
+                (-3, -1,  2), (-3, -2, -1), (-3,  1, -2), (-3,  2,  1)
+            ].iter()) {
+            let (x, y, z) = *expected;
+            let expected = Point3 {x, y, z};
+            assert_eq!(point, expected);
+            assert_eq!(point, transform.transformed(start));
+            /*
+            // This doesn't work.
+            // Maybe I won't need it.
+
+            let inverse = -transform;
+            assert_eq!(transform + inverse, Transform::new());
+            println!("point:     {}", point);
+            println!("transform: {:?}", transform);
+            println!("inverse:   {:?}", inverse);
+            assert_eq!(inverse.transformed(point), start);
+
+             */
+        }
+    }
+
+    #[test]
+    fn experiments() {
+        let s1a = Point3 {x: 0, y: 0, z: 0};
+        let s1b = Point3 {x: 3, y: 2, z: 1};
+
+        let s2a = Point3 {x: 1, y: 1, z: 1};
+        let s2b = Point3 {x: -2, y: 3, z: 2};
+
+        let offset1 = s1b - s1a;
+        let offset2 = s2b - s2a;
+        let dir1 = offset1.signum();
+        let dir2 = offset2.signum();
+        for (offset, sign, test_offset, test_sign) in [("3,2,1", "1,1,1", offset1, dir1), ("-3,2,1", "-1,1,1", offset2, dir2)] {
+            let offset: Point3 = offset.parse().unwrap();
+            let sign: Point3 = sign.parse().unwrap();
+            assert_eq!(offset, test_offset);
+            assert_eq!(sign, test_sign);
+        }
+    }
+}
@@ -0,0 +1,359 @@
+use std::fmt::{Display, Formatter};
+use std::io;
+use std::io::Write;
+use std::iter::Peekable;
+use advent_code_lib::Position;
+use crate::{input_lines, Solver};
+use crate::parse_error::{parse_number, ParseError, ParseResult};
+
+pub struct Day2;
+
+impl Solver for Day2 {
+    type Input = Vec<Command>;
+    type Part1 = isize;
+    type Part2 = isize;
+
+    fn parse_lines<I: Iterator<Item=String>>(lines: I) -> anyhow::Result<Self::Input> {
+        Ok(commands_from(lines)?)
+    }
+
+    fn part1(input: &Self::Input) -> anyhow::Result<Self::Part1> {
+        Ok(Submarine::after(input, Rule::Part1).product())
+    }
+
+    fn part2(input: &Self::Input) -> anyhow::Result<Self::Part2> {
+        Ok(Submarine::after(input, Rule::Part2).product())
+    }
+}
+
+/// One step of a navigation script. A script is a whitespace-separated sequence of
+/// commands, so a `repeat` block may be written on one line or spread over several.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Command {
+    Forward(isize),
+    Back(isize),
+    Down(isize),
+    Up(isize),
+    AimReset,
+    Repeat(usize, Vec<Command>)
+}
+
+impl Display for Command {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Command::Forward(distance) => write!(f, "forward {}", distance),
+            Command::Back(distance) => write!(f, "back {}", distance),
+            Command::Down(distance) => write!(f, "down {}", distance),
+            Command::Up(distance) => write!(f, "up {}", distance),
+            Command::AimReset => write!(f, "aim reset"),
+            Command::Repeat(times, body) => {
+                write!(f, "repeat {} {{", times)?;
+                for command in body.iter() {
+                    write!(f, " {}", command)?;
+                }
+                write!(f, " }}")
+            }
+        }
+    }
+}
+
+pub fn parse_commands(filename: &str) -> ParseResult<Vec<Command>> {
+    commands_from(input_lines(filename)?).map_err(|e| e.in_file(filename))
+}
+
+pub fn commands_from<I: Iterator<Item=String>>(lines: I) -> ParseResult<Vec<Command>> {
+    let mut tokens = tokens_from(lines).into_iter().peekable();
+    let script = block_from(&mut tokens)?;
+    match tokens.next() {
+        None => Ok(script),
+        Some(token) => Err(token.error("a command"))
+    }
+}
+
+struct Token {
+    text: String,
+    line: usize,
+    column: usize
+}
+
+impl Token {
+    fn error(&self, expected: &str) -> ParseError {
+        ParseError::expected(expected, self.text.as_str()).at_line(self.line).at_column(self.column)
+    }
+}
+
+fn tokens_from<I: Iterator<Item=String>>(lines: I) -> Vec<Token> {
+    let mut tokens = Vec::new();
+    for (i, line) in lines.enumerate() {
+        let mut offset = 0;
+        for word in line.split(' ') {
+            if !word.is_empty() {
+                tokens.push(Token {text: word.to_string(), line: i + 1, column: offset + 1});
+            }
+            offset += word.len() + 1;
+        }
+    }
+    tokens
+}
+
+type Tokens = Peekable<std::vec::IntoIter<Token>>;
+
+/// Commands up to, but not including, a closing `}` or the end of input.
+fn block_from(tokens: &mut Tokens) -> ParseResult<Vec<Command>> {
+    let mut commands = Vec::new();
+    while tokens.peek().map_or(false, |t| t.text != "}") {
+        commands.push(command_from(tokens)?);
+    }
+    Ok(commands)
+}
+
+fn command_from(tokens: &mut Tokens) -> ParseResult<Command> {
+    let token = tokens.next().ok_or(ParseError::end_of_input("a command"))?;
+    match token.text.as_str() {
+        "forward" => Ok(Command::Forward(number_from(tokens)?)),
+        "back" => Ok(Command::Back(number_from(tokens)?)),
+        "down" => Ok(Command::Down(number_from(tokens)?)),
+        "up" => Ok(Command::Up(number_from(tokens)?)),
+        "aim" => {
+            expect(tokens, "reset")?;
+            Ok(Command::AimReset)
+        }
+        "repeat" => {
+            let times = number_from(tokens)?;
+            expect(tokens, "{")?;
+            let body = block_from(tokens)?;
+            expect(tokens, "}")?;
+            Ok(Command::Repeat(times, body))
+        }
+        _ => Err(token.error("forward, back, down, up, aim reset or repeat"))
+    }
+}
+
+fn number_from<N: std::str::FromStr>(tokens: &mut Tokens) -> ParseResult<N> {
+    let token = tokens.next().ok_or(ParseError::end_of_input("a number"))?;
+    parse_number(token.text.as_str()).map_err(|_| token.error("a number"))
+}
+
+fn expect(tokens: &mut Tokens, expected: &str) -> ParseResult<()> {
+    let quoted = format!("'{}'", expected);
+    let token = tokens.next().ok_or(ParseError::end_of_input(quoted.as_str()))?;
+    if token.text == expected {Ok(())} else {Err(token.error(quoted.as_str()))}
+}
+
+/// Part 1 moves the submarine directly; part 2 steers it with `down`/`up` and moves along the aim.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Rule {
+    Part1, Part2
+}
+
+/// Where the submarine is after one primitive command; `repeat` blocks are unrolled.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Step {
+    pub command: Command,
+    pub pos: Position,
+    pub aim: isize
+}
+
+impl Display for Step {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:<12} horizontal: {}, depth: {}, aim: {}", self.command.to_string(), self.pos.col, self.pos.row, self.aim)
+    }
+}
+
+/// The path a script takes under both rules, starting from the origin.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Course {
+    steps: Vec<(Step, Step)>
+}
+
+const SVG_WIDTH: usize = 800;
+const SVG_HEIGHT: usize = 600;
+const SVG_COLORS: [(Rule, &str); 2] = [(Rule::Part1, "blue"), (Rule::Part2, "red")];
+
+impl Course {
+    pub fn of(script: &[Command]) -> Self {
+        Course {steps: Submarine::trajectories(script)}
+    }
+
+    pub fn path(&self, rule: Rule) -> Vec<Position> {
+        let mut path = vec![Submarine::new().pos];
+        path.extend(self.steps.iter().map(|(part1, part2)| match rule {
+            Rule::Part1 => part1.pos,
+            Rule::Part2 => part2.pos
+        }));
+        path
+    }
+
+    /// One row per primitive command, after a row for the starting point.
+    pub fn write_csv<W: Write>(&self, out: &mut W) -> io::Result<()> {
+        writeln!(out, "step,command,part1_horizontal,part1_depth,part2_horizontal,part2_depth,part2_aim")?;
+        writeln!(out, "0,start,0,0,0,0,0")?;
+        for (i, (part1, part2)) in self.steps.iter().enumerate() {
+            writeln!(out, "{},{},{},{},{},{},{}", i + 1, part1.command,
+                     part1.pos.col, part1.pos.row, part2.pos.col, part2.pos.row, part2.aim)?;
+        }
+        Ok(())
+    }
+
+    /// Overlays both paths as polylines, with depth increasing downwards. The two rules reach
+    /// very different depths, so the drawing is stretched to fill the image on both axes.
+    pub fn write_svg<W: Write>(&self, out: &mut W) -> io::Result<()> {
+        let paths = SVG_COLORS.map(|(rule, color)| (self.path(rule), color));
+        let all = paths.iter().flat_map(|(path, _)| path.iter());
+        let (min_col, max_col) = all.clone().map(|p| p.col).fold((0, 0), |(lo, hi), c| (lo.min(c), hi.max(c)));
+        let (min_row, max_row) = all.map(|p| p.row).fold((0, 0), |(lo, hi), r| (lo.min(r), hi.max(r)));
+        writeln!(out, r#"<svg xmlns="http://www.w3.org/2000/svg" width="{}" height="{}" viewBox="{} {} {} {}" preserveAspectRatio="none">"#,
+                 SVG_WIDTH, SVG_HEIGHT, min_col, min_row, (max_col - min_col).max(1), (max_row - min_row).max(1))?;
+        for (path, color) in paths.iter() {
+            let points = path.iter().map(|p| format!("{},{}", p.col, p.row)).collect::<Vec<_>>().join(" ");
+            writeln!(out, r#"  <polyline fill="none" stroke="{}" stroke-width="2" vector-effect="non-scaling-stroke" points="{}"/>"#, color, points)?;
+        }
+        writeln!(out, "</svg>")
+    }
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct Submarine {
+    pos: Position,
+    aim: isize
+}
+
+impl Submarine {
+    pub fn new() -> Self {
+        Submarine {pos: Position::new(), aim: 0}
+    }
+
+    pub fn after(script: &[Command], rule: Rule) -> Self {
+        let mut sub = Submarine::new();
+        sub.run(script, rule, &mut |_, _| {});
+        sub
+    }
+
+    /// The submarine's state after every primitive command in `script`.
+    pub fn trace(script: &[Command], rule: Rule) -> Vec<Step> {
+        let mut steps = Vec::new();
+        Submarine::new().run(script, rule, &mut |command, sub| {
+            steps.push(Step {command: command.clone(), pos: sub.pos, aim: sub.aim});
+        });
+        steps
+    }
+
+    /// Pairs each step of the part 1 trajectory with the same step under part 2.
+    pub fn trajectories(script: &[Command]) -> Vec<(Step, Step)> {
+        Submarine::trace(script, Rule::Part1).into_iter()
+            .zip(Submarine::trace(script, Rule::Part2))
+            .collect()
+    }
+
+    pub fn product(&self) -> isize {
+        self.pos.col * self.pos.row
+    }
+
+    pub fn report(&self) {
+        println!("Final position: horizontal: {}, depth: {}, product: {}",
+                 self.pos.col, self.pos.row, self.product());
+    }
+
+    /// Executes `script`, calling `on_step` after each primitive command.
+    pub fn run<F: FnMut(&Command, &Submarine)>(&mut self, script: &[Command], rule: Rule, on_step: &mut F) {
+        for command in script.iter() {
+            if let Command::Repeat(times, body) = command {
+                for _ in 0..*times {
+                    self.run(body, rule, on_step);
+                }
+            } else {
+                self.execute(command, rule);
+                on_step(command, self);
+            }
+        }
+    }
+
+    fn execute(&mut self, command: &Command, rule: Rule) {
+        match (command, rule) {
+            (Command::Forward(distance), Rule::Part1) => self.pos += Position::from((*distance, 0)),
+            (Command::Back(distance), Rule::Part1) => self.pos += Position::from((-distance, 0)),
+            (Command::Down(distance), Rule::Part1) => self.pos += Position::from((0, *distance)),
+            (Command::Up(distance), Rule::Part1) => self.pos += Position::from((0, -distance)),
+            (Command::Forward(distance), Rule::Part2) => self.pos += Position::from((*distance, distance * self.aim)),
+            (Command::Back(distance), Rule::Part2) => self.pos += Position::from((-distance, -distance * self.aim)),
+            (Command::Down(distance), Rule::Part2) => self.aim += distance,
+            (Command::Up(distance), Rule::Part2) => self.aim -= distance,
+            (Command::AimReset, _) => self.aim = 0,
+            (Command::Repeat(_, _), _) => panic!("run() unrolls repeat blocks")
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn script(s: &str) -> Vec<Command> {
+        commands_from(s.lines().map(|line| line.to_string())).unwrap()
+    }
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(script("forward 5\nrepeat 2 {\n  down 3 back 1\n}\naim reset"), vec![
+            Command::Forward(5),
+            Command::Repeat(2, vec![Command::Down(3), Command::Back(1)]),
+            Command::AimReset
+        ]);
+        let nested = "repeat 2 { forward 1 repeat 3 { up 2 } }";
+        assert_eq!(script(nested).iter().map(|c| c.to_string()).collect::<Vec<_>>(), vec![nested]);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let error = |s: &str| commands_from(s.lines().map(|line| line.to_string())).unwrap_err().to_string();
+        assert_eq!(error("forward 5\nsideways 2"), "2:1: expected forward, back, down, up, aim reset or repeat, found 'sideways'");
+        assert_eq!(error("down x"), "1:6: expected a number, found 'x'");
+        assert_eq!(error("repeat 2 { up 1"), "expected '}', found end of input");
+        assert_eq!(error("up 1 }"), "1:6: expected a command, found '}'");
+        assert_eq!(error("aim high"), "1:5: expected 'reset', found 'high'");
+    }
+
+    #[test]
+    fn test_trace() {
+        let script = script("down 2 repeat 2 { forward 3 } aim reset back 1");
+        let trace = Submarine::trace(&script, Rule::Part2);
+        assert_eq!(trace.iter().map(|s| (s.pos.col, s.pos.row, s.aim)).collect::<Vec<_>>(),
+                   vec![(0, 0, 2), (3, 6, 2), (6, 12, 2), (6, 12, 0), (5, 12, 0)]);
+        assert_eq!(trace[1].command, Command::Forward(3));
+        let last = Submarine::trace(&script, Rule::Part1).pop().unwrap();
+        assert_eq!((last.pos.col, last.pos.row), (5, 2));
+        let (part1, part2) = Submarine::trajectories(&script).pop().unwrap();
+        assert_eq!((part1, part2), (last, Submarine::trace(&script, Rule::Part2).pop().unwrap()));
+    }
+
+    #[test]
+    fn test_course() {
+        let course = Course::of(&script("forward 2 down 1 forward 3"));
+        assert_eq!(course.path(Rule::Part1), [(0, 0), (2, 0), (2, 1), (5, 1)].map(Position::from));
+        assert_eq!(course.path(Rule::Part2), [(0, 0), (2, 0), (2, 0), (5, 3)].map(Position::from));
+
+        let mut csv = Vec::new();
+        course.write_csv(&mut csv).unwrap();
+        assert_eq!(String::from_utf8(csv).unwrap().lines().collect::<Vec<_>>(), vec![
+            "step,command,part1_horizontal,part1_depth,part2_horizontal,part2_depth,part2_aim",
+            "0,start,0,0,0,0,0",
+            "1,forward 2,2,0,2,0,0",
+            "2,down 1,2,1,2,0,1",
+            "3,forward 3,5,1,5,3,1"
+        ]);
+
+        let mut svg = Vec::new();
+        course.write_svg(&mut svg).unwrap();
+        let svg = String::from_utf8(svg).unwrap();
+        assert!(svg.contains(r#"viewBox="0 0 5 3""#));
+        assert!(svg.contains(r#"stroke="blue" stroke-width="2" vector-effect="non-scaling-stroke" points="0,0 2,0 2,1 5,1""#));
+        assert!(svg.contains(r#"points="0,0 2,0 2,0 5,3""#));
+    }
+
+    #[test]
+    fn test_example() {
+        let script = script("forward 5\ndown 5\nforward 8\nup 3\ndown 8\nforward 2");
+        assert_eq!(Submarine::after(&script, Rule::Part1).product(), 150);
+        assert_eq!(Submarine::after(&script, Rule::Part2).product(), 900);
+    }
+}
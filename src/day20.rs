@@ -0,0 +1,254 @@
+use std::cmp::{max, min};
+use std::collections::HashMap;
+use std::{iter, mem};
+use std::fmt::{Display, Formatter};
+use advent_code_lib::{OffsetRowMajorPositionIterator, Position, RowMajorPositionIterator};
+use anyhow::anyhow;
+use bits::BitArray;
+use crate::{input_lines, Solver};
+use crate::parse_error::{next_line, ParseError, ParseResult};
+
+const ON:  char = '#';
+const OFF: char = '.';
+pub const PART_1_ITER: usize = 2;
+pub const PART_2_ITER: usize = 50;
+const BORDER: isize = 1;
+const ALGORITHM_LEN: usize = 512;
+
+pub struct Day20;
+
+impl Solver for Day20 {
+    type Input = (Image, BitArray);
+    type Part1 = usize;
+    type Part2 = usize;
+
+    fn parse_lines<I: Iterator<Item=String>>(lines: I) -> anyhow::Result<Self::Input> {
+        Ok(input_from(lines)?)
+    }
+
+    fn part1(input: &Self::Input) -> anyhow::Result<Self::Part1> {
+        lit_after(&input.0, &input.1, PART_1_ITER, false).ok_or(anyhow!("Infinitely many pixels are lit"))
+    }
+
+    fn part2(input: &Self::Input) -> anyhow::Result<Self::Part2> {
+        lit_after(&input.0, &input.1, PART_2_ITER, false).ok_or(anyhow!("Infinitely many pixels are lit"))
+    }
+}
+
+pub fn read_input(filename: &str) -> ParseResult<(Image, BitArray)> {
+    input_from(input_lines(filename)?).map_err(|e| e.in_file(filename))
+}
+
+pub fn input_from<I: Iterator<Item=String>>(mut lines: I) -> ParseResult<(Image, BitArray)> {
+    let algorithm = next_line(&mut lines, "an image enhancement algorithm")
+        .and_then(|line| read_enhancement_algorithm(line.as_str()))
+        .map_err(|e| e.at_line(1))?;
+    let blank = next_line(&mut lines, "a blank line").map_err(|e| e.at_line(2))?;
+    if !blank.is_empty() {
+        return Err(ParseError::expected("a blank line", blank.as_str()).at_line(2));
+    }
+    let image = read_image(&mut lines)?;
+    Ok((image, algorithm))
+}
+
+pub fn lit_after(image: &Image, algorithm: &BitArray, steps: usize, show: bool) -> Option<usize> {
+    ImageEnhancer::new(image.clone(), algorithm.clone())
+        .take(steps + 1).enumerate()
+        .inspect(|(i, image)| {
+            if show {
+                println!("After step {}", i);
+                println!("{}", image);
+                println!("{:?}", image.num_lit());
+            }
+        })
+        .last().and_then(|(_, image)| image.num_lit())
+}
+
+/// `column` locates `c` in its line for error messages.
+fn code2pixel(c: char, column: usize) -> ParseResult<bool> {
+    match c {
+        ON => Ok(true),
+        OFF => Ok(false),
+        other => Err(ParseError::expected(format!("'{}' or '{}'", ON, OFF).as_str(), other.to_string().as_str()).at_column(column))
+    }
+}
+
+fn pixel2code(pixel: bool) -> char {
+    if pixel {ON} else {OFF}
+}
+
+fn read_enhancement_algorithm(line: &str) -> ParseResult<BitArray> {
+    let len = line.chars().count();
+    if len != ALGORITHM_LEN {
+        let expected = format!("{} pixels", ALGORITHM_LEN);
+        return Err(ParseError::expected(expected.as_str(), format!("{} pixels", len).as_str()));
+    }
+    let mut result = BitArray::new();
+    for (i, code) in line.chars().enumerate() {
+        result.add(code2pixel(code, i + 1)?);
+    }
+    Ok(result)
+}
+
+/// The image starts on the third line of the input, and every row must be as wide as the first.
+fn read_image<I: Iterator<Item=String>>(lines: &mut I) -> ParseResult<Image> {
+    let mut image = Image::new();
+    let mut width = None;
+    for (row, line) in lines.enumerate() {
+        let located = |e: ParseError| e.at_line(row + 3);
+        let row_width = line.chars().count();
+        if *width.get_or_insert(row_width) != row_width {
+            let expected = format!("a row of {} pixels", width.unwrap());
+            return Err(located(ParseError::expected(expected.as_str(), line.as_str())));
+        }
+        for (col, code) in line.chars().enumerate() {
+            image.set(col as isize, row as isize, code2pixel(code, col + 1).map_err(located)?);
+        }
+    }
+    Ok(image)
+}
+
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct Image {
+    bits: HashMap<Position,bool>,
+    infinite_expanse: bool,
+    min_row: isize,
+    min_col: isize,
+    max_row: isize,
+    max_col: isize
+}
+
+impl Image {
+    fn new() -> Self {Image {bits: HashMap::new(), infinite_expanse: false, min_row: 0, min_col: 0, max_row: 0, max_col: 0}}
+
+    fn lowest_pos(&self) -> Position {
+        Position::from((self.min_col, self.min_row))
+    }
+
+    fn width(&self) -> usize {
+        (self.max_col - self.min_col + 1) as usize
+    }
+
+    fn set(&mut self, col: isize, row: isize, value: bool) {
+        let p = Position::from((col, row));
+        self.bits.insert(p, value);
+        self.min_row = min(row, self.min_row);
+        self.min_col = min(col, self.min_col);
+        self.max_row = max(row, self.max_row);
+        self.max_col = max(col, self.max_col);
+    }
+
+    fn on(&self, p: Position) -> bool {
+        self.bits.get(&p).map_or(self.infinite_expanse, |b| *b)
+    }
+
+    fn encoded(&self, p: Position) -> char {
+        pixel2code(self.on(p))
+    }
+
+    fn neighborhood(&self, p: Position) -> u64 {
+        let mut result = 0;
+        let baseline = p - Position::from((1, 1));
+        for pixel in RowMajorPositionIterator::new(3, 3) {
+            let pixel = pixel + baseline;
+            result *= 2;
+            if self.on(pixel) {
+                result += 1;
+            }
+        }
+        result
+    }
+
+    pub fn num_lit(&self) -> Option<usize> {
+        if self.infinite_expanse {
+            None
+        } else {
+            Some(self.bits.values().filter(|v| **v).count())
+        }
+    }
+
+    fn infinite_row(&self) -> String {
+        iter::repeat(self.infinite_char()).take(self.width() + 2).collect()
+    }
+
+    fn infinite_char(&self) -> char {
+        pixel2code(self.infinite_expanse)
+    }
+}
+
+impl Display for Image {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}\n{}", self.infinite_row(), self.infinite_char())?;
+        for pixel in OffsetRowMajorPositionIterator::new(self.min_col, self.min_row, self.max_col, self.max_row) {
+            write!(f, "{}", self.encoded(pixel))?;
+            if pixel.col == self.max_col {
+                writeln!(f, "{}", self.infinite_char())?;
+                if pixel.row < self.max_row {
+                    write!(f, "{}", self.infinite_char())?;
+                }
+            }
+        }
+        writeln!(f, "{}", self.infinite_row())?;
+        Ok(())
+    }
+}
+
+pub struct ImageEnhancer {
+    image: Image,
+    algorithm: BitArray
+}
+
+impl ImageEnhancer {
+    pub fn new(image: Image, algorithm: BitArray) -> Self {
+        ImageEnhancer {image, algorithm}
+    }
+}
+
+impl Iterator for ImageEnhancer {
+    type Item = Image;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut updated = Image::new();
+        for pixel in OffsetRowMajorPositionIterator::new(self.image.min_col - BORDER,
+                                                         self.image.min_row - BORDER,
+                                                         self.image.max_col + BORDER,
+                                                         self.image.max_row + BORDER) {
+            updated.set(pixel.col, pixel.row, self.algorithm.is_set(self.image.neighborhood(pixel)));
+        }
+        updated.infinite_expanse = updated.on(updated.lowest_pos());
+        mem::swap(&mut self.image, &mut updated);
+        Some(updated)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use advent_code_lib::Position;
+    use crate::Solver;
+    use super::{Day20, read_image};
+
+    const TEST_IMAGE: &'static str = "#..#.
+#....
+##..#
+..#..
+..###";
+
+    #[test]
+    fn test1() {
+        let image = read_image(&mut TEST_IMAGE.split_whitespace().map(|s| s.to_string())).unwrap();
+        assert_eq!(image.neighborhood(Position::from((2, 2))), 34);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let algorithm = "#.".repeat(256);
+        let error = Day20::parse_str("#..#").unwrap_err();
+        assert_eq!(error.to_string(), "1: expected 512 pixels, found '4 pixels'");
+        let error = Day20::parse_str(format!("{}\n\n#.\n.x", algorithm).as_str()).unwrap_err();
+        assert_eq!(error.to_string(), "4:2: expected '#' or '.', found 'x'");
+        let error = Day20::parse_str(format!("{}\n\n#.\n...", algorithm).as_str()).unwrap_err();
+        assert_eq!(error.to_string(), "4: expected a row of 2 pixels, found '...'");
+        let error = Day20::parse_str(format!("{}\n#.", algorithm).as_str()).unwrap_err();
+        assert_eq!(error.to_string(), "2: expected a blank line, found '#.'");
+    }
+}
\ No newline at end of file
@@ -0,0 +1,330 @@
+use std::collections::HashMap;
+use std::ops::Add;
+use advent_code_lib::{combinations_of, ExNihilo};
+use bare_metal_modulo::{MNum, ModNumC};
+use crate::{input_lines, Solver};
+use crate::parse_error::{next_line, parse_number, ParseError, ParseResult};
+
+const DIE_FACES_1: usize = 100;
+const DIE_FACES_2: usize = 3;
+const BOARD_SQUARES: usize = 10;
+const ROLLS_PER_TURN: usize = 3;
+const NUM_PLAYERS: usize = 2;
+const TARGET_SCORE_1: u128 = 1000;
+const TARGET_SCORE_2: u128 = 21;
+
+pub struct Day21;
+
+impl Solver for Day21 {
+    type Input = StartingPositions;
+    type Part1 = u128;
+    type Part2 = u128;
+
+    fn parse_lines<I: Iterator<Item=String>>(lines: I) -> anyhow::Result<Self::Input> {
+        Ok(starting_positions(lines)?)
+    }
+
+    fn part1(input: &Self::Input) -> anyhow::Result<Self::Part1> {
+        let mut game: Part1Game = Game::new(*input, DeterministicDie::create());
+        game.play_until_completion();
+        Ok(game.part_1_score())
+    }
+
+    fn part2(input: &Self::Input) -> anyhow::Result<Self::Part2> {
+        Ok(AllGamesFrom::max_wins(*input))
+    }
+}
+
+/////////////////
+// Part 1 Code //
+/////////////////
+
+pub type Part1Game = Game<DeterministicDie<DIE_FACES_1>, TARGET_SCORE_1>;
+
+pub fn part_1_game(filename: &str) -> ParseResult<Part1Game> {
+    Game::from_file(filename)
+}
+
+#[derive(Debug, Copy, Clone)]
+pub struct Game<D, const G: u128> {
+    players: [Player; NUM_PLAYERS],
+    current_player: ModNumC<usize, NUM_PLAYERS>,
+    die: D,
+    num_rolls: u128
+}
+
+/// Each player's square, counting from 0.
+pub type StartingPositions = [ModNumC<u128, BOARD_SQUARES>; NUM_PLAYERS];
+
+fn grab_nums(filename: &str) -> ParseResult<StartingPositions> {
+    starting_positions(input_lines(filename)?).map_err(|e| e.in_file(filename))
+}
+
+/// Expects one `Player N starting position: P` line per player, in order; trailing blank lines are ignored.
+pub fn starting_positions<I: Iterator<Item=String>>(mut lines: I) -> ParseResult<StartingPositions> {
+    let mut positions = [ModNumC::new(0); NUM_PLAYERS];
+    for (i, position) in positions.iter_mut().enumerate() {
+        let line = next_line(&mut lines, format!("player {}'s starting position", i + 1).as_str())
+            .map_err(|e| e.at_line(i + 1))?;
+        *position = line_num(line.as_str(), i + 1).map_err(|e| e.at_line(i + 1))?;
+    }
+    for (i, line) in lines.enumerate() {
+        if !line.trim().is_empty() {
+            return Err(ParseError::expected("end of input", line.as_str()).at_line(NUM_PLAYERS + i + 1));
+        }
+    }
+    Ok(positions)
+}
+
+impl <D:Copy + Iterator<Item=u128> + ExNihilo, const G: u128> Game<D, G> {
+    pub fn from_file(filename: &str) -> ParseResult<Self> {
+        Ok(Self::new(grab_nums(filename)?, D::create()))
+    }
+
+    pub fn new(start: StartingPositions, die: D) -> Self {
+        Game {players: start.map(Player::new), current_player: ModNumC::new(0), die, num_rolls: 0}
+    }
+
+    fn roll(&mut self) -> u128 {
+        let mut total = 0;
+        for _ in 0..ROLLS_PER_TURN {
+            total += self.die.next().unwrap();
+        }
+        total
+    }
+
+    fn mover(&mut self) -> &mut Player {
+        &mut self.players[self.current_player.a()]
+    }
+
+    pub fn play_until_completion(&mut self) {
+        loop {
+            let distance = self.roll();
+            self.mover().play_one_move(distance);
+            self.num_rolls += ROLLS_PER_TURN as u128;
+            if self.mover().total_score() >= G {
+                break;
+            } else {
+                self.current_player += 1;
+            }
+        }
+    }
+
+    pub fn part_1_score(&self) -> u128 {
+        self.players.iter().map(|p| p.total_score()).min().unwrap() * self.num_rolls
+    }
+}
+
+#[derive(Copy, Clone, Debug)]
+pub struct DeterministicDie<const F: usize> {
+    face: ModNumC<u128, F>
+}
+
+impl <const F: usize> Iterator for DeterministicDie<F> {
+    type Item = u128;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let result = self.face.a() + 1;
+        self.face += 1;
+        Some(result)
+    }
+}
+
+impl <const F: usize> ExNihilo for DeterministicDie<F> {
+    fn create() -> Self {
+        DeterministicDie {face: ModNumC::new(0)}
+    }
+}
+
+#[derive(Copy, Clone, Debug)]
+pub struct Player {
+    position: ModNumC<u128, BOARD_SQUARES>,
+    position_sum: u128,
+    moves: u128
+}
+
+fn line_num(line: &str, player: usize) -> ParseResult<ModNumC<u128, BOARD_SQUARES>> {
+    let prefix = format!("Player {} starting position: ", player);
+    let position = line.strip_prefix(prefix.as_str())
+        .ok_or(ParseError::expected(format!("'{}P'", prefix).as_str(), line))?;
+    match parse_number::<u128>(position) {
+        Ok(p) if (1..=BOARD_SQUARES as u128).contains(&p) => Ok(ModNumC::new(p - 1)),
+        _ => Err(ParseError::expected(format!("a square from 1 to {}", BOARD_SQUARES).as_str(), position)
+            .offset_by(prefix.len()))
+    }
+}
+
+impl Player {
+    fn new(position: ModNumC<u128, BOARD_SQUARES>) -> Self {
+        Player {position_sum: 0, moves: 0, position}
+    }
+
+    fn total_score(&self) -> u128 {
+        self.position_sum + self.moves
+    }
+
+    fn play_one_move(&mut self, distance: u128) {
+        self.position += distance;
+        self.moves += 1;
+        self.position_sum += self.position.a();
+    }
+}
+
+/////////////////
+// Part 2 Code //
+/////////////////
+
+pub struct AllGamesFrom {
+    wins_from: HashMap<GameKey, WinnerTally>,
+    roller: DiracRoller
+}
+
+impl AllGamesFrom {
+    pub fn part_2(filename: &str) -> ParseResult<u128> {
+        Ok(Self::max_wins(grab_nums(filename)?))
+    }
+
+    fn new() -> Self {
+        AllGamesFrom {wins_from: HashMap::new(), roller: DiracRoller::new()}
+    }
+
+    pub fn max_wins(start: [ModNumC<u128, BOARD_SQUARES>; NUM_PLAYERS]) -> u128 {
+        let mut games = AllGamesFrom::new();
+        let wins = games.get_wins_for(GameKey {locations: start, scores: [0; NUM_PLAYERS], current: ModNumC::new(0)});
+        wins.winner_count()
+    }
+
+    fn get_wins_for(&mut self, key: GameKey) -> WinnerTally {
+        match self.wins_from.get(&key) {
+            Some(wins) => *wins,
+            None => {
+                let tally = match key.winner() {
+                    None => {
+                        let mut tally = WinnerTally::new();
+                        for total_roll in self.roller.rolls() {
+                            tally = tally + self.get_wins_for(key.moved_by(total_roll as u128));
+                        }
+                        tally
+                    }
+                    Some(winner) => winner
+                };
+                self.wins_from.insert(key, tally);
+                tally
+            }
+        }
+    }
+}
+
+#[derive(Copy, Clone, Hash, Eq, PartialEq)]
+struct GameKey {
+    locations: [ModNumC<u128, BOARD_SQUARES>; NUM_PLAYERS],
+    scores: [u128; NUM_PLAYERS],
+    current: ModNumC<usize, NUM_PLAYERS>
+}
+
+impl GameKey {
+    fn winner(&self) -> Option<WinnerTally> {
+        self.scores.iter().enumerate()
+            .find(|(_, s)| **s >= TARGET_SCORE_2)
+            .map(|(p, _)| {
+                let mut result = WinnerTally::new();
+                result.tally[p] = 1;
+                result
+            })
+    }
+
+    fn moved_by(&self, roll: u128) -> GameKey {
+        let mut next = self.clone();
+        next.locations[self.current.a()] += roll;
+        next.scores[self.current.a()] += next.locations[self.current.a()].a() + 1;
+        next.current += 1;
+        next
+    }
+}
+
+#[derive(Copy, Clone, Eq, PartialEq)]
+struct WinnerTally {
+    tally: [u128; NUM_PLAYERS]
+}
+
+impl WinnerTally {
+    fn new() -> Self {
+        WinnerTally {tally:[0; NUM_PLAYERS]}
+    }
+
+    fn winner_count(&self) -> u128 {
+        self.tally.iter().max().copied().unwrap()
+    }
+}
+
+impl Add for WinnerTally {
+    type Output = WinnerTally;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        let mut result = WinnerTally::new();
+        self.tally.iter().zip(rhs.tally.iter()).enumerate()
+            .for_each(|(i, (a, b))| result.tally[i] += a + b);
+        result
+    }
+}
+
+struct DiracRoller {
+    rolls: Vec<u128>
+}
+
+impl DiracRoller {
+    fn new() -> Self {
+        let mut rolls = Vec::new();
+        combinations_of(DIE_FACES_2, &mut |arr: &[usize; 3]| {
+            rolls.push((arr.len() + arr.iter().copied().sum::<usize>()) as u128);
+        });
+        assert_eq!(rolls.len(), DIE_FACES_2.pow(ROLLS_PER_TURN as u32));
+        DiracRoller {rolls}
+    }
+
+    fn rolls(&self) -> Vec<u128> {
+        self.rolls.clone()
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    impl Player {    
+        fn space_at(&self) -> u128 {
+            self.position.a() + 1
+        }
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let error = Day21::parse_str("Player 1 starting position: 4").unwrap_err();
+        assert_eq!(error.to_string(), "2: expected player 2's starting position, found end of input");
+        let error = Day21::parse_str("Player 1 starting position: 4\nPlayer 2 starting position: 11").unwrap_err();
+        assert_eq!(error.to_string(), "2:29: expected a square from 1 to 10, found '11'");
+        let error = Day21::parse_str("Player 1 starting position: 4\nPlayer 2 starting position: 8\nPlayer 3 starting position: 1").unwrap_err();
+        assert_eq!(error.to_string(), "3: expected end of input, found 'Player 3 starting position: 1'");
+        let error = Day21::parse_str("Player 2 starting position: 4").unwrap_err();
+        assert_eq!(error.to_string(), "1: expected 'Player 1 starting position: P', found 'Player 2 starting position: 4'");
+    }
+
+    #[test]
+    fn test_example_part_1() {
+        let mut game = part_1_game("ex/day21.txt").unwrap();
+        game.play_until_completion();
+        println!("{:?}", game);
+        assert_eq!(game.players[0].total_score(), 1000);
+        assert_eq!(game.players[0].space_at(), 10);
+        assert_eq!(game.players[1].total_score(), 745);
+        assert_eq!(game.players[1].space_at(), 3);
+        assert_eq!(game.num_rolls, 993);
+        assert_eq!(game.part_1_score(), 739785);
+    }
+
+    #[test]
+    fn test_examples() {
+        crate::answers::assert_examples(21);
+    }
+}
@@ -0,0 +1,373 @@
+use std::cmp::{max, min};
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+use advent_code_lib::combinations_of;
+use itertools::Itertools;
+use crate::Solver;
+use crate::parse_error::{parse_each, parse_lines, parse_number, ParseError, ParseResult};
+
+const PART_1_MAX: isize = 50;
+const ON: &'static str = "on";
+const OFF: &'static str = "off";
+const DIMENSIONS: usize = 3;
+
+pub struct Day22;
+
+impl Solver for Day22 {
+    type Input = AllActions;
+    type Part1 = usize;
+    type Part2 = usize;
+
+    fn parse_lines<I: Iterator<Item=String>>(lines: I) -> anyhow::Result<Self::Input> {
+        Ok(AllActions::from_iter(lines)?)
+    }
+
+    fn part1(input: &Self::Input) -> anyhow::Result<Self::Part1> {
+        Ok(input.part1().total_on())
+    }
+
+    fn part2(input: &Self::Input) -> anyhow::Result<Self::Part2> {
+        Ok(input.total_on())
+    }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct AllActions {
+    actions: Vec<CuboidAction>
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct CuboidAction {
+    action: CubeState, region: Cuboid
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum CubeState {On, Off}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct Cuboid {
+    ranges: [RangeDim; DIMENSIONS]
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct RangeDim {
+    start: isize, end: isize
+}
+
+impl AllActions {
+    pub fn from_file(filename: &str) -> ParseResult<Self> {
+        Ok(AllActions {actions: parse_lines(filename)?})
+    }
+
+    pub fn from_iter<I: Iterator<Item=String>>(lines: I) -> ParseResult<Self> {
+        Ok(AllActions {actions: parse_each(lines)?})
+    }
+
+    pub fn part1(&self) -> Self {
+        let checker_range = RangeDim::from(-PART_1_MAX, PART_1_MAX).unwrap();
+        let checker_cube = Cuboid {ranges: [checker_range; DIMENSIONS]};
+        AllActions {actions: self.actions.iter()
+            .filter(|cuboid| checker_cube.envelops(&cuboid.region))
+            .copied().collect()}
+    }
+
+    pub fn total_on(&self) -> usize {
+        let mut activated = Vec::new();
+        for action in self.actions.iter() {
+            activated = action.apply_to(&activated);
+        }
+        activated.iter().map(|cuboid| cuboid.num_cubes()).sum()
+    }
+}
+
+impl CuboidAction {
+    fn apply_to(&self, activated: &Vec<Cuboid>) -> Vec<Cuboid> {
+        let mut result = Vec::new();
+        for cuboid in activated.iter() {
+            match self.region.intersection(cuboid) {
+                None => {result.push(cuboid.clone())}
+                Some(intersection) => {
+                    for piece in cuboid.break_out(&intersection) {
+                        if piece != intersection {
+                            result.push(piece);
+                        }
+                    }
+                }
+            }
+        }
+        if self.action == CubeState::On {
+            result.push(self.region.clone());
+        }
+        result
+    }
+}
+
+impl Cuboid {
+    fn from_iter<I:Iterator<Item=RangeDim>>(mut iter: I) -> Self {
+        let skeleton = [RangeDim {start: 0, end: 0}; DIMENSIONS];
+        Self {ranges: skeleton.map(|_| iter.next().unwrap())}
+    }
+
+    pub fn num_cubes(&self) -> usize {
+        self.ranges.iter().map(|range| range.span()).product()
+    }
+
+    fn envelops(&self, other: &Cuboid) -> bool {
+        self.ranges.iter().zip(other.ranges.iter()).all(|(a, b)| a.envelops(b))
+    }
+
+    pub fn break_out(&self, piece: &Cuboid) -> Vec<Cuboid> {
+        let break_outs = self.ranges.iter()
+            .zip(piece.ranges.iter())
+            .filter_map(|(a, b)| a.break_out(b))
+            .collect_vec();
+        let num_break_outs = break_outs.iter().map(|v| v.len()).collect_vec();
+        let mut result = Vec::new();
+        if break_outs.len() == DIMENSIONS {
+            combinations_of(num_break_outs.iter().copied().max().unwrap(),
+                            &mut |indices: &[usize; DIMENSIONS]| {
+                                if indices.iter().zip(num_break_outs.iter()).all(|(i, len)| *i < *len) {
+                                    let mut ranges = [RangeDim { start: 0, end: 0 }; DIMENSIONS];
+                                    for i in 0..DIMENSIONS {
+                                        ranges[i] = break_outs[i][indices[i]];
+                                    }
+                                    result.push(Cuboid {ranges});
+                                }
+                            });
+        }
+        result
+    }
+
+    pub fn intersection(&self, other: &Cuboid) -> Option<Self> {
+        let ranges = self.ranges.iter()
+            .zip(other.ranges.iter())
+            .filter_map(|(a, b)| a.intersection(b))
+            .collect_vec();
+        if ranges.len() == DIMENSIONS {
+            Some(Self::from_iter(ranges.iter().copied()))
+        } else {
+            None
+        }
+    }
+}
+
+impl RangeDim {
+    fn from(start: isize, end: isize) -> Option<Self> {
+        if start <= end {
+            Some(RangeDim {start, end})
+        } else {
+            None
+        }
+    }
+
+    fn span(&self) -> usize {
+        (self.end - self.start + 1) as usize
+    }
+
+    fn contains(&self, value: isize) -> bool {self.start <= value && value <= self.end}
+
+    fn envelops(&self, other: &RangeDim) -> bool {
+        self.contains(other.start) && self.contains(other.end)
+    }
+
+    fn break_out(&self, piece: &RangeDim) -> Option<Vec<RangeDim>> {
+        self.intersection(piece)
+            .map(|intersection| {
+                let mut result = vec![intersection];
+                if intersection.start > self.start {
+                    result.push(RangeDim::from(self.start, intersection.start - 1).unwrap());
+                }
+                if intersection.end < self.end {
+                    result.push(RangeDim::from(intersection.end + 1, self.end).unwrap());
+                }
+                result
+            })
+    }
+
+    fn intersection(&self, other: &RangeDim) -> Option<RangeDim> {
+        RangeDim::from(max(self.start, other.start), min(self.end, other.end))
+    }
+}
+
+impl FromStr for CuboidAction {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (state, region) = s.split_once(' ').unwrap_or((s, ""));
+        let action = state.parse::<CubeState>()?;
+        let region = region.parse::<Cuboid>().map_err(|e| e.offset_by(state.len() + 1))?;
+        Ok(CuboidAction {action, region})
+    }
+}
+
+impl FromStr for Cuboid {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut ranges = Vec::new();
+        let mut offset = 0;
+        for part in s.split(',') {
+            let (_, range) = part.split_once('=')
+                .ok_or(ParseError::expected("'x=', 'y=' or 'z='", part).offset_by(offset))?;
+            let range_offset = offset + part.len() - range.len();
+            ranges.push(range.parse::<RangeDim>().map_err(|e| e.offset_by(range_offset))?);
+            offset += part.len() + 1;
+        }
+        if ranges.len() != DIMENSIONS {
+            return Err(ParseError::expected("ranges for x, y and z", s));
+        }
+        Ok(Self::from_iter(ranges.into_iter()))
+    }
+}
+
+impl FromStr for RangeDim {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (start, end) = s.split_once("..").ok_or(ParseError::expected("'start..end'", s))?;
+        let end_offset = start.len() + 2;
+        let start = parse_number(start)?;
+        let end = parse_number(end).map_err(|e| e.offset_by(end_offset))?;
+        Self::from(start, end).ok_or(ParseError::expected("a range whose start is at most its end", s))
+    }
+}
+
+impl FromStr for CubeState {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            ON => Ok(CubeState::On),
+            OFF => Ok(CubeState::Off),
+            other => Err(ParseError::expected("'on' or 'off'", other))
+        }
+    }
+}
+
+impl Display for CubeState {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", match self {CubeState::On => ON, CubeState::Off => OFF})
+    }
+}
+
+impl Display for RangeDim {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}..{}", self.start, self.end)
+    }
+}
+
+impl Display for CuboidAction {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} {}", self.action, self.region)
+    }
+}
+
+impl Display for Cuboid {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "x={},y={},z={}", self.ranges[0], self.ranges[1], self.ranges[2])
+    }
+}
+
+impl Display for AllActions {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for action in self.actions.iter() {
+            writeln!(f, "{}", action)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use quickcheck::{Arbitrary, Gen};
+    use quickcheck_macros::quickcheck;
+    use super::*;
+
+    impl Arbitrary for RangeDim {
+        fn arbitrary(g: &mut Gen) -> Self {
+            let start = (i8::arbitrary(g) % 16) as isize;
+            RangeDim {start, end: start + (u8::arbitrary(g) % 12) as isize}
+        }
+    }
+
+    impl Arbitrary for Cuboid {
+        fn arbitrary(g: &mut Gen) -> Self {
+            Cuboid {ranges: [RangeDim::arbitrary(g), RangeDim::arbitrary(g), RangeDim::arbitrary(g)]}
+        }
+    }
+
+    #[quickcheck]
+    fn prop_break_out_partitions(whole: Cuboid, piece: Cuboid) -> bool {
+        let pieces = whole.break_out(&piece);
+        match whole.intersection(&piece) {
+            None => pieces.is_empty(),
+            Some(intersection) =>
+                pieces.contains(&intersection)
+                    && pieces.iter().all(|p| whole.envelops(p))
+                    && pieces.iter().map(|p| p.num_cubes()).sum::<usize>() == whole.num_cubes()
+                    && pieces.iter().enumerate().all(|(i, p)| pieces[i + 1..].iter().all(|q| p.intersection(q).is_none()))
+        }
+    }
+
+    const CUBOIDS_1: [&'static str; 2] = ["x=10..12,y=10..12,z=10..12", "x=11..13,y=11..13,z=11..13"];
+
+    #[test]
+    fn test_simple_count() {
+        for cuboid in CUBOIDS_1.iter() {
+            let cuboid: Cuboid = cuboid.parse().unwrap();
+            assert_eq!(cuboid.num_cubes(), 27);
+        }
+    }
+
+    #[test]
+    fn test_examples() {
+        crate::answers::assert_examples(22);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let error = "of x=1..2,y=1..2,z=1..2".parse::<CuboidAction>().unwrap_err();
+        assert_eq!(error.to_string(), "expected 'on' or 'off', found 'of'");
+        let error = "on x=1..2,y=1..b,z=1..2".parse::<CuboidAction>().unwrap_err();
+        assert_eq!(error.column, Some(16));
+        assert_eq!(error.to_string(), "16: expected a number, found 'b'");
+        assert!("on x=1..2,y=1..2".parse::<CuboidAction>().is_err());
+        assert!("on x=2..1,y=1..2,z=1..2".parse::<CuboidAction>().is_err());
+    }
+
+    #[test]
+    fn test_parse_str() {
+        let example = "on x=10..12,y=10..12,z=10..12\non x=11..13,y=11..13,z=11..13\n\
+                       off x=9..11,y=9..11,z=9..11\non x=10..10,y=10..10,z=10..10";
+        assert_eq!(Day22::parse_str(example).unwrap().total_on(), 39);
+        assert_eq!(Day22::parse_reader(example.as_bytes()).unwrap(), Day22::parse_str(example).unwrap());
+        let error = Day22::parse_str("on x=1..2,y=1..2,z=1..2\nof x=1..2,y=1..2,z=1..2").unwrap_err();
+        assert_eq!(error.to_string(), "2: expected 'on' or 'off', found 'of'");
+    }
+
+    #[test]
+    fn test_break_out_1() {
+        let cuboids = CUBOIDS_1.iter().map(|s| s.parse::<Cuboid>().unwrap()).collect_vec();
+        let intersection = cuboids[0].intersection(&cuboids[1]);
+        println!("Intersection: {}", intersection.unwrap());
+        let breaks = cuboids[0].break_out(&cuboids[1]);
+        for broken in breaks.iter() {
+            println!("{}", broken);
+        }
+        let break_sum = breaks.iter().map(|b| b.num_cubes()).sum::<usize>();
+        assert_eq!(break_sum, cuboids[0].num_cubes());
+    }
+
+    #[test]
+    fn test_break_out_2() {
+        let one = "x=10..10,y=11..12,z=11..12".parse::<Cuboid>().unwrap();
+        let two = "x=9..11,y=9..11,z=9..11".parse::<Cuboid>().unwrap();
+        let intersection = one.intersection(&two).unwrap();
+        println!("Intersection: {}", intersection);
+        let broken = one.break_out(&two);
+        println!("Broken out:");
+        for cuboid in broken {
+            println!("{}", cuboid);
+        }
+    }
+}
\ No newline at end of file
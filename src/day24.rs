@@ -0,0 +1,205 @@
+use std::collections::VecDeque;
+use std::str::FromStr;
+use anyhow::{anyhow, bail};
+use bare_metal_modulo::{MNum, ModNumC};
+use itertools::Itertools;
+use crate::Solver;
+use crate::parse_error::{parse_each, parse_lines, ParseError, ParseResult};
+
+const VAR_NAMES: [char; 4] = ['w', 'x', 'y', 'z'];
+const NUM_VARS: usize = VAR_NAMES.len();
+const MODEL_NUM_LEN: usize = 14;
+
+pub struct Day24;
+
+impl Solver for Day24 {
+    type Input = ALU;
+    type Part1 = String;
+    type Part2 = String;
+
+    fn parse_lines<I: Iterator<Item=String>>(lines: I) -> anyhow::Result<Self::Input> {
+        Ok(ALU::from_iter(lines)?)
+    }
+
+    fn part1(_input: &Self::Input) -> anyhow::Result<Self::Part1> {
+        bail!("Day 24 part 1 is not solved yet")
+    }
+
+    fn part2(_input: &Self::Input) -> anyhow::Result<Self::Part2> {
+        bail!("Day 24 part 2 is not solved yet")
+    }
+}
+
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct ALU {
+    program: Vec<ALUInstruction>,
+    data: [isize; NUM_VARS]
+}
+
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+enum OpCode {
+    Add, Mul, Div, Mod, Eql, Inp
+}
+
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+struct ALUInstruction {
+    op: OpCode,
+    arg1: ModNumC<usize,NUM_VARS>,
+    arg2: ALUArg2
+}
+
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+enum ALUArg2 {
+    Var(ModNumC<usize,NUM_VARS>), Val(isize)
+}
+
+impl ALU {
+    pub fn from_file(filename: &str) -> ParseResult<Self> {
+        Ok(ALU {program: parse_lines(filename)?, data: [0; NUM_VARS]})
+    }
+
+    pub fn from_iter<I: Iterator<Item=String>>(lines: I) -> ParseResult<Self> {
+        Ok(ALU {program: parse_each(lines)?, data: [0; NUM_VARS]})
+    }
+
+    pub fn run(&mut self, inputs: &Vec<isize>) -> anyhow::Result<()> {
+        let mut input_queue: VecDeque<isize> = inputs.iter().copied().collect();
+        for (i, instruction) in self.program.iter().enumerate() {
+            let a = self.data[instruction.arg1.a()];
+            let b = instruction.arg2.value(&self.data);
+            self.data[instruction.arg1.a()] = match instruction.op {
+                OpCode::Add => a + b,
+                OpCode::Mul => a * b,
+                OpCode::Div => a / b,
+                OpCode::Mod => a % b,
+                OpCode::Eql => if a == b {1} else {0},
+                OpCode::Inp => input_queue.pop_front()
+                    .ok_or(anyhow!("Instruction {} needs more than {} inputs", i + 1, inputs.len()))?
+            };
+        }
+        Ok(())
+    }
+
+    pub fn reset(&mut self) {
+        self.data = self.data.map(|_| 0);
+    }
+}
+
+impl ALUArg2 {
+    fn value(&self, data: &[isize; NUM_VARS]) -> isize {
+        match self {
+            ALUArg2::Var(v) => data[v.a()],
+            ALUArg2::Val(v) => *v
+        }
+    }
+}
+
+impl FromStr for ALUInstruction {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parts = s.split_whitespace().collect_vec();
+        let op = *parts.get(0).ok_or(ParseError::end_of_input("an instruction"))?;
+        let arg1 = var_from(parts.get(1).ok_or(ParseError::end_of_input("a variable"))?)
+            .map_err(|e| e.offset_by(op.len() + 1))?;
+        if op == "inp" {
+            Ok(ALUInstruction {op: OpCode::Inp, arg1, arg2: ALUArg2::Val(0)})
+        } else {
+            let arg2 = parts.get(2).ok_or(ParseError::end_of_input("a variable or number"))?
+                .parse::<ALUArg2>()
+                .map_err(|e| e.offset_by(op.len() + parts[1].len() + 2))?;
+            match op {
+                "add" => Ok(ALUInstruction {op: OpCode::Add, arg1, arg2}),
+                "mul" => Ok(ALUInstruction {op: OpCode::Mul, arg1, arg2}),
+                "div" => Ok(ALUInstruction {op: OpCode::Div, arg1, arg2}),
+                "mod" => Ok(ALUInstruction {op: OpCode::Mod, arg1, arg2}),
+                "eql" => Ok(ALUInstruction {op: OpCode::Eql, arg1, arg2}),
+                other => Err(ParseError::expected("add, mul, div, mod, eql or inp", other))
+            }
+        }
+    }
+}
+
+impl FromStr for ALUArg2 {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.parse::<isize>() {
+            Ok(v) => Ok(ALUArg2::Val(v)),
+            Err(_) => Ok(ALUArg2::Var(var_from(s)?))
+        }
+    }
+}
+
+fn var_from(v: &str) -> ParseResult<ModNumC<usize,NUM_VARS>> {
+    VAR_NAMES.iter()
+        .position(|n| v.len() == 1 && v.starts_with(*n))
+        .map(|i| ModNumC::new(i))
+        .ok_or(ParseError::expected("one of w, x, y or z", v))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_a() {
+        let mut alu = ALU::from_file("ex/day24a.txt").unwrap();
+        for i in 1..1000 {
+            alu.run(&vec![i]).unwrap();
+            assert_eq!(alu.data[1], -i);
+        }
+    }
+
+    #[test]
+    fn test_b() {
+        let mut alu = ALU::from_file("ex/day24b.txt").unwrap();
+        for i in 1..1000 {
+            for j in -1..=1 {
+                let second = i * 3 + j;
+                alu.run(&vec![i, second]).unwrap();
+                assert_eq!(alu.data[3], if second == i * 3 {1} else {0});
+            }
+        }
+    }
+
+    #[test]
+    fn test_c() {
+        let mut alu = ALU::from_file("ex/day24c.txt").unwrap();
+        for i in 1..1000 {
+            alu.run(&vec![i]).unwrap();
+            let target = i % 16;
+            let mut output = 0;
+            for j in 0..alu.data.len() {
+                output *= 2;
+                output += alu.data[j];
+            }
+            assert_eq!(target, output);
+            alu.reset();
+        }
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let error = "jmp x 1".parse::<ALUInstruction>().unwrap_err();
+        assert_eq!(error.to_string(), "expected add, mul, div, mod, eql or inp, found 'jmp'");
+        let error = "mul x q".parse::<ALUInstruction>().unwrap_err();
+        assert_eq!(error.to_string(), "7: expected one of w, x, y or z, found 'q'");
+        assert!("inp".parse::<ALUInstruction>().is_err());
+    }
+
+    fn expand(i: isize) -> Vec<isize> {
+        (0..MODEL_NUM_LEN).map(|d| i + d as isize).collect()
+    }
+
+    #[test]
+    fn test_puzzle_input() {
+        let mut alu = ALU::from_file("in/day24.txt").unwrap();
+        for i in 1..=9 {
+            let input = expand(i);
+            alu.run(&input).unwrap();
+            println!("{}, {:?}", alu.data[3], input);
+            alu.reset();
+        }
+    }
+}
\ No newline at end of file
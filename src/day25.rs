@@ -0,0 +1,178 @@
+use std::cmp::max;
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+use std::io;
+use advent_code_lib::{Position, RowMajorPositionIterator};
+use anyhow::bail;
+use itertools::Itertools;
+use crate::{input_lines, Solver};
+use crate::parse_error::{ParseError, ParseResult};
+
+const EAST: char = '>';
+const SOUTH: char = 'v';
+const EMPTY: char = '.';
+
+pub struct Day25;
+
+impl Solver for Day25 {
+    type Input = Cucumbers;
+    type Part1 = usize;
+    type Part2 = usize;
+
+    fn parse_lines<I: Iterator<Item=String>>(lines: I) -> anyhow::Result<Self::Input> {
+        Ok(Cucumbers::from_iter(lines)?)
+    }
+
+    fn part1(input: &Self::Input) -> anyhow::Result<Self::Part1> {
+        Ok(input.iter().count())
+    }
+
+    fn part2(_input: &Self::Input) -> anyhow::Result<Self::Part2> {
+        bail!("Day 25 has no part 2")
+    }
+}
+
+pub fn part1(filename: &str) -> io::Result<usize> {
+    Ok(Cucumbers::from_file(filename)?.iter().count())
+}
+
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+enum Cucumber {
+    East, South, Empty
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Cucumbers {
+    grid: HashMap<Position, Cucumber>,
+    width: isize,
+    height: isize
+}
+
+pub struct CIterator {
+    value: Option<Cucumbers>
+}
+
+impl Cucumbers {
+    pub fn from_file(filename: &str) -> ParseResult<Self> {
+        Self::from_iter(input_lines(filename)?).map_err(|e| e.in_file(filename))
+    }
+
+    pub fn from_iter<I: Iterator<Item=String>>(iter: I) -> ParseResult<Self> {
+        let mut grid = HashMap::new();
+        let mut width = 0;
+        let mut height = 0;
+        for (row, line) in iter.enumerate() {
+            if row > 0 && line.chars().count() as isize != width + 1 {
+                return Err(ParseError::expected(format!("a row of {} cucumbers", width + 1).as_str(), line.as_str()).at_line(row + 1));
+            }
+            let row = row as isize;
+            height = max(height, row);
+            for (col, c) in line.chars().enumerate() {
+                let col = col as isize;
+                width = max(width, col);
+                grid.insert(Position::from((col, row)), match c {
+                    EAST => Cucumber::East,
+                    SOUTH => Cucumber::South,
+                    EMPTY => Cucumber::Empty,
+                    other => return Err(ParseError::expected("'>', 'v' or '.'", other.to_string().as_str())
+                        .at_line(row as usize + 1).at_column(col as usize + 1))
+                });
+            }
+        }
+        width += 1;
+        height += 1;
+        Ok(Cucumbers {grid, width, height})
+    }
+
+    pub fn iter(&self) -> CIterator {
+        CIterator {value: Some(self.clone())}
+    }
+
+    fn move_all(&mut self, dir: Cucumber) {
+        let movers = self.grid.iter()
+            .filter(|(_, c)| **c == dir)
+            .filter_map(|(p, c)| c.next_spot(*p, self).map(|n| (*c, *p, n)))
+            .collect_vec();
+        for (mover, start, end) in movers {
+            self.grid.insert(end, mover);
+            self.grid.insert(start, Cucumber::Empty);
+        }
+    }
+}
+
+impl Cucumber {
+    fn next_spot(&self, p: Position, grid: &Cucumbers) -> Option<Position> {
+        match self {
+            Cucumber::Empty => None,
+            Cucumber::East => Some(Position::from(((p.col + 1) % grid.width, p.row))),
+            Cucumber::South => Some(Position::from((p.col, (p.row + 1) % grid.height)))
+        }.filter(|n| grid.grid.get(n).map_or(false, |c| *c == Cucumber::Empty))
+    }
+}
+
+impl Iterator for CIterator {
+    type Item = Cucumbers;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let result = self.value.clone();
+        self.value = self.value.as_ref().and_then(|current| {
+            let mut future = current.clone();
+            future.move_all(Cucumber::East);
+            future.move_all(Cucumber::South);
+            if future == *current {None} else {Some(future)}
+        });
+        result
+    }
+}
+
+impl Display for Cucumbers {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for p in RowMajorPositionIterator::new(self.width as usize, self.height as usize) {
+            if p.col == 0 && p.row > 0 {
+                writeln!(f)?;
+            }
+            write!(f, "{}", self.grid.get(&p).unwrap())?;
+        }
+        writeln!(f)
+    }
+}
+
+impl Display for Cucumber {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", match self {Cucumber::East => EAST, Cucumber::South => SOUTH, Cucumber::Empty => EMPTY})
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_errors() {
+        let error = Day25::parse_str("..>\n.x.").unwrap_err();
+        assert_eq!(error.to_string(), "2:2: expected '>', 'v' or '.', found 'x'");
+        let error = Day25::parse_str("..>\n.v").unwrap_err();
+        assert_eq!(error.to_string(), "2: expected a row of 3 cucumbers, found '.v'");
+    }
+
+    #[test]
+    fn test_io() {
+        let grid = Cucumbers::from_file("ex/day25.txt").unwrap();
+        let copy = Cucumbers::from_iter(format!("{}", grid).split_whitespace().map(|line| line.to_owned())).unwrap();
+        assert_eq!(grid.width, copy.width);
+        assert_eq!(grid.height, copy.height);
+        assert_eq!(grid, copy);
+    }
+
+    #[test]
+    fn test_one_iteration() {
+        let grid = Cucumbers::from_file("ex/day25.txt").unwrap();
+        let next = Cucumbers::from_file("ex/day25_1.txt").unwrap();
+        assert_eq!(grid.iter().skip(1).next().unwrap(), next);
+    }
+
+    #[test]
+    fn test_examples() {
+        crate::answers::assert_examples(25);
+    }
+}
\ No newline at end of file
@@ -0,0 +1,200 @@
+use anyhow::anyhow;
+use bits::BitArray;
+use num::BigUint;
+use crate::Solver;
+use crate::parse_error::{ParseError, ParseResult};
+
+pub struct Day3;
+
+impl Solver for Day3 {
+    type Input = Vec<BitArray>;
+    type Part1 = BigUint;
+    type Part2 = BigUint;
+
+    fn parse_lines<I: Iterator<Item=String>>(lines: I) -> anyhow::Result<Self::Input> {
+        Ok(lines.enumerate()
+            .map(|(i, s)| s.parse().map_err(|_| ParseError::expected("a binary number", s.as_str()).at_line(i + 1)))
+            .collect::<ParseResult<_>>()?)
+    }
+
+    fn part1(input: &Self::Input) -> anyhow::Result<Self::Part1> {
+        let (gamma, epsilon) = gamma_epsilon(input);
+        Ok(product(&gamma, &epsilon))
+    }
+
+    fn part2(input: &Self::Input) -> anyhow::Result<Self::Part2> {
+        let (oxygen, co2) = oxygen_co2(input).ok_or(anyhow!("Empty diagnostic report"))?;
+        Ok(product(&oxygen, &co2))
+    }
+}
+
+pub fn gamma_epsilon(binary_nums: &Vec<BitArray>) -> (BitArray, BitArray) {
+    let gamma = ColumnStats::of(binary_nums).gamma(TieBreak::One);
+    let epsilon = !&gamma;
+    (gamma, epsilon)
+}
+
+pub fn oxygen_co2(binary_nums: &Vec<BitArray>) -> Option<(BitArray, BitArray)> {
+    Some((OXYGEN.rate(binary_nums)?.clone(), CO2.rate(binary_nums)?.clone()))
+}
+
+pub fn product(num1: &BitArray, num2: &BitArray) -> BigUint {
+    BigUint::from(num1) * BigUint::from(num2)
+}
+
+/// Rows shorter than the widest one are treated as having leading zeros.
+fn bit(row: &BitArray, column: u64) -> bool {
+    column < row.len() && row.is_set(column)
+}
+
+/// Per-column totals for a whole report, gathered in a single pass over its rows.
+/// Column 0 is the least significant bit.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct ColumnStats {
+    pub total: u64,
+    pub ones: Vec<u64>
+}
+
+impl ColumnStats {
+    pub fn of(rows: &[BitArray]) -> Self {
+        ColumnStats::weighted(rows.iter().map(|row| (row, 1)))
+    }
+
+    /// Each row counts `weight` times toward its column totals.
+    pub fn weighted<'a, I: Iterator<Item=(&'a BitArray, u64)>>(rows: I) -> Self {
+        let mut stats = ColumnStats::default();
+        for (row, weight) in rows {
+            if stats.ones.len() < row.len() as usize {
+                stats.ones.resize(row.len() as usize, 0);
+            }
+            stats.total += weight;
+            for column in (0..row.len()).filter(|c| row.is_set(*c)) {
+                stats.ones[column as usize] += weight;
+            }
+        }
+        stats
+    }
+
+    pub fn width(&self) -> u64 {
+        self.ones.len() as u64
+    }
+
+    pub fn zeros(&self, column: u64) -> u64 {
+        self.total - self.ones[column as usize]
+    }
+
+    pub fn most_common(&self, column: u64, ties: TieBreak) -> bool {
+        Keep::MostCommon.target(self.ones[column as usize], self.zeros(column), ties)
+    }
+
+    /// The most common bit in every column.
+    pub fn gamma(&self, ties: TieBreak) -> BitArray {
+        (0..self.width()).map(|column| self.most_common(column, ties)).collect()
+    }
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum TieBreak {
+    One, Zero
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Keep {
+    MostCommon, LeastCommon
+}
+
+impl Keep {
+    fn target(&self, ones: u64, zeros: u64, ties: TieBreak) -> bool {
+        if ones == zeros {
+            ties == TieBreak::One
+        } else {
+            (ones > zeros) == (*self == Keep::MostCommon)
+        }
+    }
+}
+
+/// Narrows a report down to one row, from the most significant column to the least,
+/// by keeping only the rows whose bit matches the `keep` bit among those remaining.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct Rating {
+    pub keep: Keep,
+    pub ties: TieBreak
+}
+
+pub const OXYGEN: Rating = Rating {keep: Keep::MostCommon, ties: TieBreak::One};
+pub const CO2: Rating = Rating {keep: Keep::LeastCommon, ties: TieBreak::Zero};
+
+impl Rating {
+    pub fn rate<'a>(&self, rows: &'a [BitArray]) -> Option<&'a BitArray> {
+        self.rate_weighted(rows, |_| 1)
+    }
+
+    /// Like `rate`, but with commonality judged by the total `weight` of each row index.
+    /// If duplicates survive every column, the earliest of them is the rating.
+    pub fn rate_weighted<'a, W: Fn(usize) -> u64>(&self, rows: &'a [BitArray], weight: W) -> Option<&'a BitArray> {
+        let mut remaining = (0..rows.len()).collect::<Vec<_>>();
+        let mut column = rows.iter().map(|row| row.len()).max()?;
+        while remaining.len() > 1 && column > 0 {
+            column -= 1;
+            let (ones, zeros) = remaining.iter().fold((0, 0), |(ones, zeros), i| {
+                if bit(&rows[*i], column) {(ones + weight(*i), zeros)} else {(ones, zeros + weight(*i))}
+            });
+            let target = self.keep.target(ones, zeros, self.ties);
+            // Zero weights can make the target a bit no remaining row has; such a column decides nothing.
+            if remaining.iter().any(|i| bit(&rows[*i], column) == target) {
+                remaining.retain(|i| bit(&rows[*i], column) == target);
+            }
+        }
+        remaining.first().map(|i| &rows[*i])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: [&str; 12] = ["00100", "11110", "10110", "10111", "10101", "01111", "00111", "11100", "10000", "11001", "00010", "01010"];
+
+    fn rows(strs: &[&str]) -> Vec<BitArray> {
+        strs.iter().map(|s| s.parse().unwrap()).collect()
+    }
+
+    #[test]
+    fn test_example() {
+        let report = rows(&EXAMPLE);
+        let stats = ColumnStats::of(&report);
+        assert_eq!(stats.total, 12);
+        assert_eq!(stats.ones, vec![5, 7, 8, 5, 7]);
+        let (gamma, epsilon) = gamma_epsilon(&report);
+        assert_eq!((gamma.to_string(), epsilon.to_string()), ("10110".to_string(), "01001".to_string()));
+        let (oxygen, co2) = oxygen_co2(&report).unwrap();
+        assert_eq!((oxygen.to_string(), co2.to_string()), ("10111".to_string(), "01010".to_string()));
+        assert_eq!(oxygen_co2(&vec![]), None);
+    }
+
+    #[test]
+    fn test_wide() {
+        let one = format!("1{}", "0".repeat(99));
+        let report = rows(&[one.as_str(), "1", "11"]);
+        let stats = ColumnStats::of(&report);
+        assert_eq!(stats.width(), 100);
+        assert_eq!(stats.gamma(TieBreak::One).to_string(), format!("{}1", "0".repeat(99)));
+        assert_eq!(BigUint::from(OXYGEN.rate(&report).unwrap()), BigUint::from(3u8));
+        assert_eq!(BigUint::from(CO2.rate(&report).unwrap()), BigUint::from(2u8).pow(99));
+    }
+
+    #[test]
+    fn test_policies() {
+        let report = rows(&["10", "01"]);
+        assert_eq!(ColumnStats::of(&report).gamma(TieBreak::Zero).to_string(), "00");
+        let least_ones = Rating {keep: Keep::LeastCommon, ties: TieBreak::One};
+        assert_eq!(least_ones.rate(&report).unwrap().to_string(), "10");
+
+        let report = rows(&["110", "101", "011"]);
+        let weights = [1, 1, 5];
+        assert_eq!(OXYGEN.rate(&report).unwrap().to_string(), "110");
+        assert_eq!(OXYGEN.rate_weighted(&report, |i| weights[i]).unwrap().to_string(), "011");
+        let stats = ColumnStats::weighted(report.iter().zip(weights));
+        assert_eq!(stats.gamma(TieBreak::One).to_string(), "011");
+    }
+}
@@ -0,0 +1,298 @@
+use std::collections::{HashMap, HashSet};
+use std::mem::replace;
+use std::str::FromStr;
+use advent_code_lib::ExNihilo;
+use anyhow::anyhow;
+use crate::{input_lines, Solver};
+use crate::parse_error::{next_line, numbers_in, ParseError, ParseResult};
+
+pub struct Day4;
+
+impl Solver for Day4 {
+    type Input = BingoGame;
+    type Part1 = usize;
+    type Part2 = usize;
+
+    fn parse_lines<I: Iterator<Item=String>>(lines: I) -> anyhow::Result<Self::Input> {
+        Ok(BingoGame::from_iter(lines)?)
+    }
+
+    fn part1(input: &Self::Input) -> anyhow::Result<Self::Part1> {
+        input.scores().next().ok_or(anyhow!("No board ever won"))
+    }
+
+    fn part2(input: &Self::Input) -> anyhow::Result<Self::Part2> {
+        input.scores().last().ok_or(anyhow!("No board ever won"))
+    }
+}
+
+/// A way for a board to win, judged on the square most recently marked.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum WinRule {
+    Rows,
+    Columns,
+    /// Either corner-to-corner diagonal; only square boards have them.
+    Diagonals,
+    FourCorners,
+    Blackout,
+    /// This many consecutive marked squares horizontally, vertically or diagonally.
+    InARow(usize)
+}
+
+pub const STANDARD_RULES: [WinRule; 2] = [WinRule::Rows, WinRule::Columns];
+
+impl FromStr for WinRule {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "rows" => Ok(WinRule::Rows),
+            "columns" => Ok(WinRule::Columns),
+            "diagonals" => Ok(WinRule::Diagonals),
+            "corners" => Ok(WinRule::FourCorners),
+            "blackout" => Ok(WinRule::Blackout),
+            other => other.strip_suffix("-in-a-row")
+                .and_then(|n| n.parse().ok())
+                .map(WinRule::InARow)
+                .ok_or(ParseError::expected("rows, columns, diagonals, corners, blackout or N-in-a-row", other))
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct BingoGame {
+    calls: Vec<usize>,
+    boards: Vec<BingoBoard>,
+    rules: Vec<WinRule>
+}
+
+impl BingoGame {
+    pub fn from_file(filename: &str) -> ParseResult<Self> {
+        Self::from_iter(input_lines(filename)?).map_err(|e| e.in_file(filename))
+    }
+
+    pub fn from_iter<I: Iterator<Item=String>>(mut lines: I) -> ParseResult<Self> {
+        let calls = next_line(&mut lines, "the called numbers")
+            .and_then(|line| numbers_in(line.as_str(), ','))
+            .map_err(|e| e.at_line(1))?;
+        lines.next(); // Skip blank line
+        let mut boards = Vec::new();
+        let mut board = BingoBoard::create();
+        for (i, line) in lines.enumerate() {
+            if !line.is_empty() {
+                let row = numbers_in(line.as_str(), ' ').map_err(|e| e.at_line(i + 3))?;
+                if board.num_rows > 0 && row.len() != board.num_cols {
+                    let expected = format!("{} numbers", board.num_cols);
+                    return Err(ParseError::expected(expected.as_str(), line.as_str()).at_line(i + 3));
+                }
+                board.add_row(&row);
+            } else if board.num_rows > 0 {
+                boards.push(replace(&mut board, BingoBoard::create()));
+            }
+        }
+        if board.num_rows > 0 {
+            boards.push(board);
+        }
+        Ok(BingoGame {calls, boards, rules: STANDARD_RULES.to_vec()})
+    }
+
+    /// A board wins as soon as it satisfies any one of `rules`.
+    pub fn with_rules(mut self, rules: &[WinRule]) -> Self {
+        self.rules = rules.to_vec();
+        self
+    }
+
+    pub fn num_boards(&self) -> usize {
+        self.boards.len()
+    }
+
+    pub fn replay(&self) -> Replay<'_> {
+        Replay {calls: self.calls.iter(), boards: self.boards.clone(), rules: &self.rules}
+    }
+
+    /// Scores in the order the boards win; boards winning on the same call are in board order.
+    pub fn scores(&self) -> impl Iterator<Item=usize> + '_ {
+        self.replay().flat_map(|round| round.winners.into_iter().map(|win| win.score))
+    }
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct Win {
+    /// Index of the board in the order it was read.
+    pub board: usize,
+    pub score: usize
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Round {
+    pub call: usize,
+    pub winners: Vec<Win>
+}
+
+/// Yields every call with the boards that first won on it, stopping once every board has won.
+/// A board that has won is no longer marked.
+pub struct Replay<'a> {
+    calls: std::slice::Iter<'a, usize>,
+    boards: Vec<BingoBoard>,
+    rules: &'a [WinRule]
+}
+
+impl <'a> Iterator for Replay<'a> {
+    type Item = Round;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.boards.iter().all(|board| board.winner) {
+            return None;
+        }
+        let call = *self.calls.next()?;
+        let winners = self.boards.iter_mut().enumerate()
+            .filter(|(_, board)| !board.winner)
+            .filter_map(|(i, board)| {
+                board.mark(call, self.rules);
+                if board.winner {Some(Win {board: i, score: board.score(call)})} else {None}
+            })
+            .collect();
+        Some(Round {call, winners})
+    }
+}
+
+const DIRECTIONS: [(isize, isize); 4] = [(1, 0), (0, 1), (1, 1), (1, -1)];
+
+#[derive(Eq, PartialEq, Clone)]
+pub struct BingoBoard {
+    num2pos: HashMap<usize,(usize,usize)>,
+    num_rows: usize,
+    num_cols: usize,
+    marked: HashSet<(usize,usize)>,
+    unmarked: HashSet<usize>,
+    winner: bool
+}
+
+impl ExNihilo for BingoBoard {
+    fn create() -> Self {
+        BingoBoard {num2pos: HashMap::new(), num_cols: 0, num_rows: 0,
+            marked: HashSet::new(), unmarked: HashSet::new(), winner: false}
+    }
+}
+
+impl BingoBoard {
+    fn add_row(&mut self, row: &Vec<usize>) {
+        for (col_num, num) in row.iter().enumerate() {
+            self.num2pos.insert(*num, (col_num, self.num_rows));
+            self.unmarked.insert(*num);
+        }
+        self.num_rows += 1;
+        if row.len() > self.num_cols {
+            self.num_cols = row.len();
+        }
+    }
+
+    pub fn mark(&mut self, num: usize, rules: &[WinRule]) {
+        self.unmarked.remove(&num);
+        if let Some(pos) = self.num2pos.get(&num).copied() {
+            self.marked.insert(pos);
+            self.winner = self.winner || rules.iter().any(|rule| self.wins_at(pos, *rule));
+        }
+    }
+
+    pub fn winner(&self) -> bool {
+        self.winner
+    }
+
+    pub fn score(&self, call: usize) -> usize {
+        self.unmarked.iter().sum::<usize>() * call
+    }
+
+    fn wins_at(&self, pos: (usize, usize), rule: WinRule) -> bool {
+        match rule {
+            WinRule::Rows => self.run_through(pos, DIRECTIONS[0]) == self.num_cols,
+            WinRule::Columns => self.run_through(pos, DIRECTIONS[1]) == self.num_rows,
+            WinRule::Diagonals => self.num_rows == self.num_cols &&
+                DIRECTIONS[2..].iter().any(|dir| self.run_through(pos, *dir) == self.num_rows),
+            WinRule::FourCorners => [0, self.num_cols - 1].iter()
+                .all(|c| [0, self.num_rows - 1].iter().all(|r| self.marked.contains(&(*c, *r)))),
+            WinRule::Blackout => self.marked.len() == self.num2pos.len(),
+            WinRule::InARow(n) => DIRECTIONS.iter().any(|dir| self.run_through(pos, *dir) >= n)
+        }
+    }
+
+    /// The number of consecutive marked squares along `dir` that include `pos`.
+    fn run_through(&self, pos: (usize, usize), dir: (isize, isize)) -> usize {
+        1 + [1, -1].iter().map(|sign| {
+            let (mut col, mut row) = (pos.0 as isize, pos.1 as isize);
+            let mut length = 0;
+            loop {
+                col += sign * dir.0;
+                row += sign * dir.1;
+                if col < 0 || row < 0 || !self.marked.contains(&(col as usize, row as usize)) {
+                    return length;
+                }
+                length += 1;
+            }
+        }).sum::<usize>()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn game(calls: &str, boards: &[&str]) -> BingoGame {
+        let text = format!("{}\n\n{}", calls, boards.join("\n\n"));
+        BingoGame::from_iter(text.lines().map(|line| line.to_string())).unwrap()
+    }
+
+    #[test]
+    fn test_example() {
+        let game = BingoGame::from_file("ex/day4.txt").unwrap();
+        assert_eq!(game.num_boards(), 3);
+        assert_eq!(game.scores().next(), Some(4512));
+        assert_eq!(game.scores().last(), Some(1924));
+    }
+
+    #[test]
+    fn test_rules() {
+        let board = "1 2 3\n4 5 6\n7 8 9";
+        let winning_call = |calls: &str, rules: &[WinRule]| {
+            game(calls, &[board]).with_rules(rules).replay()
+                .find(|round| !round.winners.is_empty())
+                .map(|round| round.call)
+        };
+        assert_eq!(winning_call("1,5,4,9,6", &STANDARD_RULES), Some(6));
+        assert_eq!(winning_call("1,5,4,9,6", &[WinRule::Diagonals]), Some(9));
+        assert_eq!(winning_call("3,1,5,9,7,2", &[WinRule::FourCorners]), Some(7));
+        assert_eq!(winning_call("1,2,3,4,5,6,7,8", &[WinRule::Blackout]), None);
+        assert_eq!(winning_call("1,2,3,4,5,6,7,8,9", &[WinRule::Blackout]), Some(9));
+        assert_eq!(winning_call("7,1,3,5", &[WinRule::InARow(2)]), Some(5));
+        assert_eq!("4-in-a-row".parse::<WinRule>().unwrap(), WinRule::InARow(4));
+        assert!("sideways".parse::<WinRule>().is_err());
+    }
+
+    #[test]
+    fn test_larger_boards() {
+        let board = (0..7).map(|r| (0..7).map(|c| (r * 7 + c).to_string()).collect::<Vec<_>>().join(" "))
+            .collect::<Vec<_>>().join("\n");
+        let game = game("8,16,24,32,40,1", &[board.as_str(), "1 2"]).with_rules(&[WinRule::InARow(5)]);
+        let rounds = game.replay().collect::<Vec<_>>();
+        assert_eq!(rounds.len(), 6);
+        assert_eq!(rounds[4].winners, vec![Win {board: 0, score: (1176 - 120) * 40}]);
+    }
+
+    #[test]
+    fn test_replay() {
+        let game = game("5,1,2,3,4", &["1 5\n9 7", "5 2\n8 6", "3 4\n5 10"]);
+        let rounds = game.replay().collect::<Vec<_>>();
+        assert_eq!(rounds.iter().map(|round| round.call).collect::<Vec<_>>(), vec![5, 1, 2, 3]);
+        assert!(rounds[0].winners.is_empty());
+        assert_eq!(rounds[1].winners, vec![Win {board: 0, score: 16}]);
+        assert_eq!(rounds[2].winners, vec![Win {board: 1, score: 28}]);
+        assert_eq!(game.scores().nth(2), Some(42));
+    }
+
+    #[test]
+    fn test_ragged_board() {
+        let text = "1,2\n\n1 2 3\n4 5";
+        let error = BingoGame::from_iter(text.lines().map(|line| line.to_string())).err().unwrap();
+        assert_eq!(error.to_string(), "4: expected 3 numbers, found '4 5'");
+    }
+}
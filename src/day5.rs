@@ -0,0 +1,453 @@
+use std::collections::{HashMap, HashSet};
+use std::io;
+use std::io::Write;
+use std::str::FromStr;
+use advent_code_lib::Position;
+use hash_histogram::HashHistogram;
+use crate::Solver;
+use crate::parse_error::{numbers_in, parse_each, parse_lines, ParseError, ParseResult};
+
+const MIN_OVERLAP: usize = 2;
+
+pub struct Day5;
+
+impl Solver for Day5 {
+    type Input = Vec<LineSegment>;
+    type Part1 = usize;
+    type Part2 = usize;
+
+    fn parse_lines<I: Iterator<Item=String>>(lines: I) -> anyhow::Result<Self::Input> {
+        Ok(segments_from_lines(lines)?)
+    }
+
+    fn part1(input: &Self::Input) -> anyhow::Result<Self::Part1> {
+        Ok(score(&count_intersections(input, false)))
+    }
+
+    fn part2(input: &Self::Input) -> anyhow::Result<Self::Part2> {
+        Ok(score(&count_intersections(input, true)))
+    }
+}
+
+pub fn segments_from(filename: &str) -> ParseResult<Vec<LineSegment>> {
+    parse_lines(filename)
+}
+
+pub fn segments_from_lines<I: Iterator<Item=String>>(lines: I) -> ParseResult<Vec<LineSegment>> {
+    parse_each(lines)
+}
+
+pub fn count_intersections(segments: &Vec<LineSegment>, with_diagonals: bool) -> HashHistogram<Position> {
+    count_rasterised(segments, Slopes::including_diagonals(with_diagonals))
+}
+
+pub fn count_rasterised(segments: &Vec<LineSegment>, slopes: Slopes) -> HashHistogram<Position> {
+    let mut counts = HashHistogram::new();
+    for segment in segments.iter() {
+        for p in segment.rasterise(slopes) {
+            counts.bump(&p);
+        }
+    }
+    counts
+}
+
+pub fn score(counts: &HashHistogram<Position>) -> usize {
+    counts.iter()
+        .filter(|(_, count)| **count >= MIN_OVERLAP)
+        .count()
+}
+
+/// Same as `score(&count_intersections(segments, with_diagonals))`, but works on whole segments
+/// instead of points, so its cost depends on the number of segments rather than their lengths.
+///
+/// Every point lies on exactly one line of each `Orientation`. A point is an overlap if
+/// segments on one of its lines cover it twice, or if segments on two of its lines each cover it.
+/// The first kind is found by sweeping along each line; the second only happens where lines cross.
+/// Like `Slopes::FortyFive`, this skips segments that have none of the four orientations.
+pub fn count_overlaps(segments: &Vec<LineSegment>, with_diagonals: bool) -> usize {
+    let mut lines: HashMap<Orientation, HashMap<isize, Vec<(isize, isize)>>> = HashMap::new();
+    for (segment, orientation) in segments.iter()
+        .filter(|s| with_diagonals || !s.is_diagonal())
+        .filter_map(|s| s.orientation().map(|o| (s, o))) {
+        let (t1, t2) = (orientation.along(segment.start), orientation.along(segment.end));
+        lines.entry(orientation).or_default()
+            .entry(orientation.line_of(segment.start)).or_default()
+            .push((t1.min(t2), t1.max(t2)));
+    }
+    let swept = lines.iter()
+        .flat_map(|(orientation, lines)| lines.iter().map(move |(line, spans)| ((*orientation, *line), Sweep::of(spans))))
+        .collect::<HashMap<_,_>>();
+
+    let mut crossings = HashSet::new();
+    for ((o1, line1), sweep1) in swept.iter() {
+        for ((o2, line2), sweep2) in swept.iter().filter(|((o2, _), _)| o1 < o2) {
+            if let Some(p) = o1.crossing(*line1, *o2, *line2) {
+                if sweep1.covered.contains(o1.along(p)) && sweep2.covered.contains(o2.along(p)) {
+                    crossings.insert(p);
+                }
+            }
+        }
+    }
+
+    let shared = swept.values().map(|sweep| sweep.shared.len()).sum::<usize>();
+    // A crossing point is an overlap exactly once, however many lines share it.
+    let shared_at_crossings = crossings.iter()
+        .map(|p| lines.keys()
+            .filter(|o| swept.get(&(**o, o.line_of(*p))).map_or(false, |s| s.shared.contains(o.along(*p))))
+            .count())
+        .sum::<usize>();
+    shared + crossings.len() - shared_at_crossings
+}
+
+/// The four directions a vent line may run in, each with a unit step of positive column,
+/// or of positive row for vertical lines.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
+enum Orientation {
+    Horizontal, Vertical, Diagonal, AntiDiagonal
+}
+
+impl Orientation {
+    fn step(&self) -> (isize, isize) {
+        match self {
+            Orientation::Horizontal => (1, 0),
+            Orientation::Vertical => (0, 1),
+            Orientation::Diagonal => (1, 1),
+            Orientation::AntiDiagonal => (1, -1)
+        }
+    }
+
+    /// Identifies which of the parallel lines with this orientation `p` lies on.
+    fn line_of(&self, p: Position) -> isize {
+        let (dc, dr) = self.step();
+        p.col * dr - p.row * dc
+    }
+
+    /// How far along its line `p` is.
+    fn along(&self, p: Position) -> isize {
+        if *self == Orientation::Vertical {p.row} else {p.col}
+    }
+
+    /// The grid point where two lines cross, if they are not parallel and meet at a grid point.
+    fn crossing(&self, line: isize, other: Orientation, other_line: isize) -> Option<Position> {
+        let ((dc1, dr1), (dc2, dr2)) = (self.step(), other.step());
+        let det = dc1 * dr2 - dr1 * dc2;
+        let col = dc1 * other_line - dc2 * line;
+        let row = dr1 * other_line - dr2 * line;
+        if det == 0 || col % det != 0 || row % det != 0 {
+            None
+        } else {
+            Some(Position::from((col / det, row / det)))
+        }
+    }
+}
+
+/// Disjoint, sorted, inclusive intervals along one line.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+struct Intervals(Vec<(isize, isize)>);
+
+impl Intervals {
+    fn contains(&self, t: isize) -> bool {
+        let i = self.0.partition_point(|(_, end)| *end < t);
+        self.0.get(i).map_or(false, |(start, _)| *start <= t)
+    }
+
+    fn len(&self) -> usize {
+        self.0.iter().map(|(start, end)| (end - start + 1) as usize).sum()
+    }
+
+    fn push(&mut self, start: isize, end: isize) {
+        match self.0.last_mut() {
+            Some((_, last_end)) if *last_end + 1 == start => *last_end = end,
+            _ => self.0.push((start, end))
+        }
+    }
+}
+
+/// What the segments on a single line cover, found by sweeping across their endpoints.
+struct Sweep {
+    covered: Intervals,
+    shared: Intervals
+}
+
+impl Sweep {
+    fn of(spans: &[(isize, isize)]) -> Self {
+        let mut events = spans.iter()
+            .flat_map(|(start, end)| [(*start, 1), (end + 1, -1)])
+            .collect::<Vec<_>>();
+        events.sort();
+        let mut sweep = Sweep {covered: Intervals::default(), shared: Intervals::default()};
+        let mut depth = 0;
+        for (i, (t, change)) in events.iter().enumerate() {
+            depth += change;
+            if let Some((next, _)) = events.get(i + 1).filter(|(next, _)| next > t) {
+                if depth >= 1 {
+                    sweep.covered.push(*t, next - 1);
+                }
+                if depth >= MIN_OVERLAP as isize {
+                    sweep.shared.push(*t, next - 1);
+                }
+            }
+        }
+        sweep
+    }
+}
+
+/// Which segments get rasterised. Puzzle inputs only have axis-aligned and 45-degree segments.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Slopes {
+    AxisAligned, FortyFive, Any
+}
+
+impl Slopes {
+    pub fn including_diagonals(with_diagonals: bool) -> Self {
+        if with_diagonals {Slopes::FortyFive} else {Slopes::AxisAligned}
+    }
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct LineSegment {
+    start: Position,
+    end: Position
+}
+
+impl LineSegment {
+    pub fn is_diagonal(&self) -> bool {
+        self.start.row != self.end.row && self.start.col != self.end.col
+    }
+
+    fn orientation(&self) -> Option<Orientation> {
+        let (width, height) = ((self.end.col - self.start.col).abs(), (self.end.row - self.start.row).abs());
+        if width != 0 && height != 0 && width != height {
+            return None;
+        }
+        Some(match (find_offset(self.start.col, self.end.col), find_offset(self.start.row, self.end.row)) {
+            (0, _) => Orientation::Vertical,
+            (_, 0) => Orientation::Horizontal,
+            (dc, dr) if dc == dr => Orientation::Diagonal,
+            _ => Orientation::AntiDiagonal
+        })
+    }
+
+    pub fn points(&self, with_diagonals: bool) -> LineSegmentPoints {
+        self.rasterise(Slopes::including_diagonals(with_diagonals))
+    }
+
+    pub fn rasterise(&self, slopes: Slopes) -> LineSegmentPoints {
+        let (width, height) = ((self.end.col - self.start.col).abs(), (self.end.row - self.start.row).abs());
+        let included = match slopes {
+            Slopes::AxisAligned => width == 0 || height == 0,
+            Slopes::FortyFive => width == 0 || height == 0 || width == height,
+            Slopes::Any => true
+        };
+        if included {
+            LineSegmentPoints::from(self.start, self.end)
+        } else {
+            LineSegmentPoints::empty()
+        }
+    }
+}
+
+fn find_offset(start: isize, end: isize) -> isize {
+    if start < end {1} else if start > end {-1} else {0}
+}
+
+/// Bresenham's line algorithm, which visits the same points as stepping one unit
+/// at a time along axis-aligned and 45-degree segments.
+pub struct LineSegmentPoints {
+    d: Position,
+    width: isize,
+    height: isize,
+    error: isize,
+    current: Position,
+    last: Position,
+    active: bool
+}
+
+impl LineSegmentPoints {
+    fn from(start: Position, end: Position) -> Self {
+        let (width, height) = ((end.col - start.col).abs(), (end.row - start.row).abs());
+        LineSegmentPoints {
+            d: Position::from((find_offset(start.col, end.col),
+                               find_offset(start.row, end.row))),
+            width, height, error: width - height,
+            current: start, last: end, active: true }
+    }
+
+    fn empty() -> Self {
+        LineSegmentPoints {d: Position::from((0, 0)), width: 0, height: 0, error: 0,
+            current: Position::from((0, 0)), last: Position::from((0, 0)), active: false}
+    }
+}
+
+impl Iterator for LineSegmentPoints {
+    type Item = Position;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.active {
+            let result = self.current;
+            self.active = self.current != self.last;
+            let doubled = 2 * self.error;
+            if doubled >= -self.height {
+                self.error -= self.height;
+                self.current.col += self.d.col;
+            }
+            if doubled <= self.width {
+                self.error += self.width;
+                self.current.row += self.d.row;
+            }
+            Some(result)
+        } else {
+            None
+        }
+    }
+}
+
+impl FromStr for LineSegment {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (start, end) = s.split_once(" -> ").ok_or(ParseError::expected("'x1,y1 -> x2,y2'", s))?;
+        Ok(LineSegment {start: position_from(start)?, end: position_from(end).map_err(|e| e.offset_by(start.len() + 4))?})
+    }
+}
+
+fn position_from(s: &str) -> ParseResult<Position> {
+    match numbers_in(s, ',')?.as_slice() {
+        [col, row] => Ok(Position::from((*col, *row))),
+        _ => Err(ParseError::expected("'x,y'", s))
+    }
+}
+
+pub fn print_diagram(counts: &HashHistogram<Position>, segments: &Vec<LineSegment>) {
+    let (max_x, max_y) = dimension(&segments);
+    let (width, height) = (max_x + 1, max_y + 1);
+    for row in 0..height {
+        print!("{}: ", row);
+        for col in 0..width {
+            let c = counts.count(&Position::from((col as isize, row as isize)));
+            if c > 0 {
+                print!("{}", c);
+            } else {
+                print!(".");
+            }
+        }
+        println!();
+    }
+}
+
+/// Writes a binary PPM image of `counts`, one pixel per point: black where no vents are,
+/// brightening through red and yellow to white at the most overlapped points.
+/// An empty histogram has nothing to draw, and is an error.
+pub fn write_heat_map<W: Write>(counts: &HashHistogram<Position>, out: &mut W) -> io::Result<()> {
+    if counts.iter().next().is_none() {
+        return Err(io::Error::new(io::ErrorKind::InvalidInput, "nothing to draw"));
+    }
+    let (min_col, max_col) = counts.iter().map(|(p, _)| p.col).fold((isize::MAX, isize::MIN), |(lo, hi), c| (lo.min(c), hi.max(c)));
+    let (min_row, max_row) = counts.iter().map(|(p, _)| p.row).fold((isize::MAX, isize::MIN), |(lo, hi), r| (lo.min(r), hi.max(r)));
+    let (width, height) = (max_col - min_col + 1, max_row - min_row + 1);
+    let hottest = counts.iter().map(|(_, count)| *count).max().unwrap_or(1);
+    writeln!(out, "P6\n{} {}\n255", width, height)?;
+    for row in min_row..=max_row {
+        let pixels = (min_col..=max_col)
+            .flat_map(|col| heat(counts.count(&Position::from((col, row))), hottest))
+            .collect::<Vec<_>>();
+        out.write_all(&pixels)?;
+    }
+    Ok(())
+}
+
+fn heat(count: usize, hottest: usize) -> [u8; 3] {
+    let level = 3.0 * count as f64 / hottest as f64;
+    [level, level - 1.0, level - 2.0].map(|channel| (255.0 * channel.clamp(0.0, 1.0)) as u8)
+}
+
+fn dimension(segments: &Vec<LineSegment>) -> (usize, usize) {
+    segments.iter().fold((0, 0), max_from)
+}
+
+fn max_from(acc: (usize, usize), seg: &LineSegment) -> (usize, usize) {
+    let points = [
+        (seg.start.col as usize, seg.start.row as usize),
+        (seg.end.col as usize, seg.end.row as usize),
+        acc];
+    (points.iter().copied().map(|(x, _)| x).max().unwrap(),
+     points.iter().copied().map(|(_, y)| y).max().unwrap())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_example() {
+        let segments = segments_from("ex/day5.txt").unwrap();
+        for (with_diagonals, expected) in [(false, 5), (true, 12)] {
+            assert_eq!(score(&count_intersections(&segments, with_diagonals)), expected);
+            assert_eq!(count_overlaps(&segments, with_diagonals), expected);
+        }
+    }
+
+    #[test]
+    fn test_huge_coordinates() {
+        let lines = ["0,0 -> 4000000000,0", "1000000000,0 -> 3000000000,0", "2000000000,5 -> 2000000000,-5",
+            "-10,-10 -> 10,10", "10,-10 -> -10,10", "0,10 -> 0,-10"];
+        let segments = segments_from_lines(lines.iter().map(|s| s.to_string())).unwrap();
+        assert_eq!(count_overlaps(&segments, false), 2000000002);
+        assert_eq!(count_overlaps(&segments, true), 2000000002);
+    }
+
+    #[test]
+    fn test_bresenham() {
+        let points = |s: &str, slopes: Slopes| s.parse::<LineSegment>().unwrap().rasterise(slopes)
+            .map(|p| (p.col, p.row)).collect::<Vec<_>>();
+        assert_eq!(points("0,0 -> 4,2", Slopes::Any), vec![(0, 0), (1, 1), (2, 1), (3, 2), (4, 2)]);
+        assert_eq!(points("1,5 -> 0,1", Slopes::Any), vec![(1, 5), (1, 4), (0, 3), (0, 2), (0, 1)]);
+        assert_eq!(points("0,0 -> 4,2", Slopes::FortyFive), vec![]);
+        assert_eq!(points("3,3 -> 0,6", Slopes::Any), points("3,3 -> 0,6", Slopes::FortyFive));
+        assert_eq!(points("3,3 -> 0,6", Slopes::FortyFive), vec![(3, 3), (2, 4), (1, 5), (0, 6)]);
+        assert_eq!(points("2,2 -> 2,2", Slopes::AxisAligned), vec![(2, 2)]);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let error = "1,2".parse::<LineSegment>().unwrap_err();
+        assert_eq!(error.to_string(), "expected 'x1,y1 -> x2,y2', found '1,2'");
+        let error = "1,2 -> 3".parse::<LineSegment>().unwrap_err();
+        assert_eq!(error.to_string(), "8: expected 'x,y', found '3'");
+        let error = Day5::parse_str("1,2 -> 3,4\n1,2 -> 3,z").unwrap_err();
+        assert_eq!(error.to_string(), "2:10: expected a number, found 'z'");
+    }
+
+    #[test]
+    fn test_heat_map() {
+        let segments = segments_from_lines(["0,0 -> 2,0", "1,0 -> 1,1"].iter().map(|s| s.to_string())).unwrap();
+        let mut ppm = Vec::new();
+        write_heat_map(&count_intersections(&segments, false), &mut ppm).unwrap();
+        let header = b"P6\n3 2\n255\n";
+        assert_eq!(&ppm[..header.len()], header);
+        let pixels = &ppm[header.len()..];
+        assert_eq!(pixels.len(), 3 * 2 * 3);
+        assert_eq!(&pixels[0..3], &[255, 127, 0]);
+        assert_eq!(&pixels[3..6], &[255, 255, 255]);
+        assert_eq!(&pixels[9..12], &[0, 0, 0]);
+
+        let diagonal = segments_from_lines(["0,0 -> 2,2"].iter().map(|s| s.to_string())).unwrap();
+        let mut ppm = Vec::new();
+        let error = write_heat_map(&count_intersections(&diagonal, false), &mut ppm).unwrap_err();
+        assert_eq!(error.to_string(), "nothing to draw");
+        assert!(ppm.is_empty());
+    }
+
+    #[test]
+    fn test_crossing_between_grid_points() {
+        let segments = segments_from_lines(["0,0 -> 1,1", "1,0 -> 0,1"].iter().map(|s| s.to_string())).unwrap();
+        assert_eq!(count_overlaps(&segments, true), 0);
+    }
+
+    #[test]
+    fn test_off_slope_segments_skipped() {
+        let segments = segments_from_lines(["0,0 -> 3,1", "2,2 -> 2,5", "3,1 -> 0,0"].iter().map(|s| s.to_string())).unwrap();
+        assert_eq!(score(&count_intersections(&segments, true)), 0);
+        assert_eq!(count_overlaps(&segments, true), 0);
+    }
+}
@@ -0,0 +1,250 @@
+use std::collections::HashMap;
+use num::{BigUint, One, Zero};
+use crate::Solver;
+use crate::parse_error::{first_line_numbers, ParseError, ParseResult};
+
+const FIRST: usize = 9;
+const REST: usize = 7;
+const PART_1_DAYS: usize = 80;
+const PART_2_DAYS: usize = 256;
+
+pub struct Day6;
+
+impl Solver for Day6 {
+    type Input = Population;
+    type Part1 = BigUint;
+    type Part2 = BigUint;
+
+    fn parse_lines<I: Iterator<Item=String>>(mut lines: I) -> anyhow::Result<Self::Input> {
+        let fish_counters = first_line_numbers::<usize, I>(&mut lines)?;
+        Ok(Population::lanternfish(&fish_counters).map_err(|e| e.at_line(1))?)
+    }
+
+    fn part1(input: &Self::Input) -> anyhow::Result<Self::Part1> {
+        Ok(input.total_after(PART_1_DAYS as u64))
+    }
+
+    fn part2(input: &Self::Input) -> anyhow::Result<Self::Part2> {
+        Ok(input.total_after(PART_2_DAYS as u64))
+    }
+}
+
+/// Fish whose timers count down once a day. A fish at 0 resets its timer to `rest - 1`
+/// and spawns a fish with timer `first - 1`, so timers run from 0 to `first - 1`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Population {
+    first: usize,
+    rest: usize,
+    census: Vec<BigUint>,
+    modulus: Option<BigUint>
+}
+
+impl Population {
+    /// Rejects timers of `first` or more, and a `rest` outside `1..=first`.
+    pub fn new(first: usize, rest: usize, fish_counters: &[usize]) -> ParseResult<Self> {
+        if !(1..=first).contains(&rest) {
+            return Err(ParseError::expected(format!("a reset timer from 1 to {}", first).as_str(), rest.to_string().as_str()));
+        }
+        let mut census = vec![BigUint::zero(); first];
+        for counter in fish_counters.iter() {
+            *census.get_mut(*counter)
+                .ok_or_else(|| ParseError::expected(format!("a timer below {}", first).as_str(), counter.to_string().as_str()))? += 1u32;
+        }
+        Ok(Population {first, rest, census, modulus: None})
+    }
+
+    pub fn lanternfish(fish_counters: &[usize]) -> ParseResult<Self> {
+        Self::new(FIRST, REST, fish_counters)
+    }
+
+    /// For day counts whose exact totals would have too many digits to compute.
+    pub fn with_modulus(mut self, modulus: BigUint) -> ParseResult<Self> {
+        if modulus.is_zero() {
+            return Err(ParseError::expected("a positive modulus", "0"));
+        }
+        self.modulus = Some(modulus);
+        Ok(self)
+    }
+
+    /// How many fish have each timer value.
+    pub fn census(&self) -> &[BigUint] {
+        &self.census
+    }
+
+    /// Each day's census is a linear function of the previous one, so the census after `days`
+    /// is the matrix of that function raised to the power `days`, found by repeated squaring.
+    pub fn total_after(&self, days: u64) -> BigUint {
+        let modulus = self.modulus.as_ref();
+        let mut census = self.census.clone();
+        let mut step = self.one_day();
+        let mut days = days;
+        while days > 0 {
+            if days % 2 == 1 {
+                census = step.apply(&census, modulus);
+            }
+            step = step.times(&step, modulus);
+            days /= 2;
+        }
+        Matrix::reduced(census.iter().sum(), modulus)
+    }
+
+    fn one_day(&self) -> Matrix {
+        let mut matrix = Matrix::zero(self.first);
+        for timer in 1..self.first {
+            matrix.0[timer - 1][timer] = BigUint::one();
+        }
+        matrix.0[self.rest - 1][0] += 1u32;
+        matrix.0[self.first - 1][0] += 1u32;
+        matrix
+    }
+}
+
+/// Square, with `self.0[to][from]` fish moving from timer `from` to timer `to` in each step.
+#[derive(Clone, Debug, Eq, PartialEq)]
+struct Matrix(Vec<Vec<BigUint>>);
+
+impl Matrix {
+    fn zero(size: usize) -> Self {
+        Matrix(vec![vec![BigUint::zero(); size]; size])
+    }
+
+    fn reduced(value: BigUint, modulus: Option<&BigUint>) -> BigUint {
+        match modulus {
+            Some(m) => value % m,
+            None => value
+        }
+    }
+
+    fn apply(&self, census: &[BigUint], modulus: Option<&BigUint>) -> Vec<BigUint> {
+        self.0.iter()
+            .map(|row| Matrix::reduced(row.iter().zip(census.iter()).map(|(a, b)| a * b).sum(), modulus))
+            .collect()
+    }
+
+    fn times(&self, other: &Matrix, modulus: Option<&BigUint>) -> Matrix {
+        let size = self.0.len();
+        Matrix((0..size)
+            .map(|row| (0..size)
+                .map(|col| Matrix::reduced((0..size).map(|k| &self.0[row][k] * &other.0[k][col]).sum(), modulus))
+                .collect())
+            .collect())
+    }
+}
+
+pub fn total_fish(fish_counters: &Vec<usize>, duration: usize, table: &mut HashMap<usize, u128>) -> u128 {
+    fish_counters.iter()
+        .map(|f| total_fish_at(duration + FIRST - f - 1, table))
+        .sum::<u128>()
+}
+
+fn total_fish_at(lifetime: usize, table: &mut HashMap<usize, u128>) -> u128 {
+    1 + (FIRST..=lifetime)
+        .step_by(REST)
+        .map(|i| {
+            let spawn_lifetime = lifetime - i;
+            if let Some(count) = table.get(&spawn_lifetime) {
+                *count
+            } else {
+                let result = total_fish_at(spawn_lifetime, table);
+                table.insert(spawn_lifetime, result);
+                result
+            }
+
+        })
+        .sum::<u128>()
+}
+
+pub fn display(table: &HashMap<usize, u128>) {
+    let mut pairs = table.iter().collect::<Vec<_>>();
+    pairs.sort();
+    println!("{:?}", pairs);
+}
+
+#[cfg(test)]
+mod tests {
+    // 0: 8 (1)
+    // 1: 7 (1)
+    // 2: 6 (1)
+    // 3: 5 (1)
+    // 4: 4 (1)
+    // 5: 3 (1)
+    // 6: 2 (1)
+    // 7: 1 (1)
+    // 8: 0 (1)
+    // 9: 6, 8 (2)
+    // 10: 5, 7 (2)
+    // 11: 4, 6 (2)
+    // 12: 3, 5 (2)
+    // 13: 2, 4 (2)
+    // 14: 1, 3 (2)
+    // 15: 0, 2 (2)
+    // 16: 6, 1, 8 (3)
+    // 17: 5, 0, 7 (3)
+    // 18: 4, 6, 6, 8 (4)
+
+    use super::*;
+
+    const EXAMPLE: [usize; 5] = [3, 4, 3, 1, 2];
+
+    fn example() -> Population {
+        Population::lanternfish(&EXAMPLE).unwrap()
+    }
+
+    #[test]
+    fn test_example() {
+        assert_eq!(example().total_after(18), BigUint::from(26u32));
+        assert_eq!(example().total_after(80), BigUint::from(5934u32));
+        assert_eq!(example().total_after(256), BigUint::from(26984457539u64));
+    }
+
+    #[test]
+    fn test_invalid_populations() {
+        assert_eq!(Population::lanternfish(&[3, 9]).unwrap_err().to_string(), "expected a timer below 9, found '9'");
+        assert_eq!(Day6::parse_str("3,4,10").unwrap_err().to_string(), "1: expected a timer below 9, found '10'");
+        assert_eq!(Population::new(4, 0, &[]).unwrap_err().to_string(), "expected a reset timer from 1 to 4, found '0'");
+        assert_eq!(Population::new(4, 5, &[]).unwrap_err().to_string(), "expected a reset timer from 1 to 4, found '5'");
+        assert!(Population::new(4, 4, &[3]).is_ok());
+        assert_eq!(example().with_modulus(BigUint::zero()).unwrap_err().to_string(), "expected a positive modulus, found '0'");
+    }
+
+    #[test]
+    fn test_matches_memo_table() {
+        let mut table = HashMap::new();
+        for days in 0..300 {
+            let memoized = total_fish(&EXAMPLE.to_vec(), days, &mut table);
+            assert_eq!(example().total_after(days as u64), BigUint::from(memoized));
+        }
+    }
+
+    #[test]
+    fn test_modulus() {
+        let modulus = BigUint::from(1_000_000_007u32);
+        let reduced = example().with_modulus(modulus.clone()).unwrap();
+        for days in [0, 1, 9, 100, 513] {
+            assert_eq!(reduced.total_after(days), example().total_after(days) % &modulus);
+        }
+        assert!(reduced.total_after(10u64.pow(12)) < modulus);
+    }
+
+    #[test]
+    fn test_other_timers() {
+        let population = Population::new(4, 2, &[0, 3]).unwrap();
+        let mut fish = vec![0, 3];
+        for days in 0..30 {
+            assert_eq!(population.total_after(days), BigUint::from(fish.len()));
+            let spawned = fish.iter().filter(|f| **f == 0).count();
+            fish = fish.iter().map(|f| if *f == 0 {population.rest - 1} else {f - 1}).collect();
+            fish.extend(std::iter::repeat_n(population.first - 1, spawned));
+        }
+    }
+
+    #[test]
+    fn test() {
+        let mut table = HashMap::new();
+        for (i, goal) in [1, 1, 1, 1, 1, 1, 1, 1, 1, 2, 2, 2, 2, 2, 2, 2, 3, 3, 4].iter().enumerate() {
+            let total = total_fish_at(i, &mut table);
+            println!("i: {} goal: {} total: {}", i, goal, total);
+            assert_eq!(total, *goal);
+        }
+    }
+}
\ No newline at end of file
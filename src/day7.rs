@@ -0,0 +1,157 @@
+use std::fmt::{Display, Formatter};
+use anyhow::anyhow;
+use crate::Solver;
+use crate::parse_error::first_line_numbers;
+
+pub struct Day7;
+
+impl Solver for Day7 {
+    type Input = Vec<isize>;
+    type Part1 = isize;
+    type Part2 = isize;
+
+    fn parse_lines<I: Iterator<Item=String>>(mut lines: I) -> anyhow::Result<Self::Input> {
+        let mut positions = first_line_numbers(&mut lines)?;
+        positions.sort();
+        Ok(positions)
+    }
+
+    fn part1(input: &Self::Input) -> anyhow::Result<Self::Part1> {
+        Ok(Linear.optimum(input).ok_or(anyhow!("No crabs"))?.fuel)
+    }
+
+    fn part2(input: &Self::Input) -> anyhow::Result<Self::Part2> {
+        Ok(Triangular.optimum(input).ok_or(anyhow!("No crabs"))?.fuel)
+    }
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct Alignment {
+    pub position: isize,
+    pub fuel: isize
+}
+
+impl Display for Alignment {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "position: {} fuel used: {}", self.position, self.fuel)
+    }
+}
+
+/// The fuel one crab burns to move `distance` steps. It must be convex and nondecreasing in
+/// the distance, which makes the total over all crabs convex in the position they align on.
+pub trait FuelCost {
+    fn fuel(&self, distance: isize) -> isize;
+
+    fn total(&self, positions: &[isize], target: isize) -> isize {
+        positions.iter().map(|p| self.fuel((p - target).abs())).sum()
+    }
+
+    /// The cheapest position, preferring the leftmost on ties.
+    fn optimum(&self, positions: &[isize]) -> Option<Alignment> {
+        ternary_search(self, positions)
+    }
+}
+
+pub struct Linear;
+
+impl FuelCost for Linear {
+    fn fuel(&self, distance: isize) -> isize {
+        distance
+    }
+
+    /// Every position between the two middle crabs costs the same, and nothing costs less.
+    fn optimum(&self, positions: &[isize]) -> Option<Alignment> {
+        let mut sorted = positions.to_vec();
+        sorted.sort_unstable();
+        let median = *sorted.get((sorted.len().max(1) - 1) / 2)?;
+        Some(Alignment {position: median, fuel: self.total(positions, median)})
+    }
+}
+
+pub struct Triangular;
+
+impl FuelCost for Triangular {
+    fn fuel(&self, distance: isize) -> isize {
+        distance * (distance + 1) / 2
+    }
+
+    /// The total's derivative is within n/2 of the mean's, so the cheapest position
+    /// is within half a step of the mean.
+    fn optimum(&self, positions: &[isize]) -> Option<Alignment> {
+        if positions.is_empty() {
+            return None;
+        }
+        let mean = positions.iter().sum::<isize>() as f64 / positions.len() as f64;
+        cheapest(self, positions, (mean - 0.5).floor() as isize..=(mean + 0.5).ceil() as isize)
+    }
+}
+
+/// Works for any convex total, narrowing the crabs' range by a third at each step.
+pub fn ternary_search<C: FuelCost + ?Sized>(cost: &C, positions: &[isize]) -> Option<Alignment> {
+    let (mut lo, mut hi) = (*positions.iter().min()?, *positions.iter().max()?);
+    while hi - lo > 2 {
+        let (m1, m2) = (lo + (hi - lo) / 3, hi - (hi - lo) / 3);
+        // If the two are equal, convexity puts a minimum between them.
+        if cost.total(positions, m1) <= cost.total(positions, m2) {
+            hi = m2;
+        } else {
+            lo = m1;
+        }
+    }
+    cheapest(cost, positions, lo..=hi)
+}
+
+fn cheapest<C: FuelCost + ?Sized, I: Iterator<Item=isize>>(cost: &C, positions: &[isize], candidates: I) -> Option<Alignment> {
+    candidates
+        .map(|position| Alignment {position, fuel: cost.total(positions, position)})
+        .min_by_key(|a| (a.fuel, a.position))
+}
+
+/// The total fuel for every position from the leftmost crab to the rightmost.
+pub fn cost_curve<C: FuelCost + ?Sized>(cost: &C, positions: &[isize]) -> Vec<Alignment> {
+    match (positions.iter().min(), positions.iter().max()) {
+        (Some(lo), Some(hi)) => (*lo..=*hi).map(|position| Alignment {position, fuel: cost.total(positions, position)}).collect(),
+        _ => Vec::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use quickcheck_macros::quickcheck;
+    use super::*;
+
+    const EXAMPLE: [isize; 10] = [16, 1, 2, 0, 4, 2, 7, 1, 2, 14];
+
+    #[test]
+    fn test_example() {
+        assert_eq!(Linear.optimum(&EXAMPLE), Some(Alignment {position: 2, fuel: 37}));
+        assert_eq!(Triangular.optimum(&EXAMPLE), Some(Alignment {position: 5, fuel: 168}));
+        assert_eq!(ternary_search(&Triangular, &EXAMPLE), Some(Alignment {position: 5, fuel: 168}));
+        let curve = cost_curve(&Linear, &EXAMPLE);
+        assert_eq!(curve.len(), 17);
+        assert_eq!(curve[10], Alignment {position: 10, fuel: 71});
+        assert_eq!(Linear.optimum(&[]), None);
+    }
+
+    struct Squared;
+
+    impl FuelCost for Squared {
+        fn fuel(&self, distance: isize) -> isize {
+            distance * distance
+        }
+    }
+
+    fn curve_minimum<C: FuelCost>(cost: &C, positions: &[isize]) -> Option<isize> {
+        cost_curve(cost, positions).iter().map(|a| a.fuel).min()
+    }
+
+    #[quickcheck]
+    fn prop_optimal(positions: Vec<i8>) -> bool {
+        let positions = positions.iter().map(|p| *p as isize).collect::<Vec<_>>();
+        let fuel = |a: Option<Alignment>| a.map(|a| a.fuel);
+        fuel(Linear.optimum(&positions)) == curve_minimum(&Linear, &positions)
+            && fuel(ternary_search(&Linear, &positions)) == curve_minimum(&Linear, &positions)
+            && fuel(Triangular.optimum(&positions)) == curve_minimum(&Triangular, &positions)
+            && fuel(Squared.optimum(&positions)) == curve_minimum(&Squared, &positions)
+    }
+}
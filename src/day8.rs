@@ -0,0 +1,301 @@
+use std::collections::{HashMap, HashSet};
+use std::str::FromStr;
+use anyhow::{anyhow, bail};
+use crate::parse_error::{parse_each, ParseError, ParseResult};
+use crate::Solver;
+
+const PATTERN_FOR: [&'static str; 10] = ["abcefg", "cf", "acdeg", "acdfg", "bcdf", "abdfg", "abdefg", "acf", "abcdefg", "abcdfg"];
+const HEX_PATTERN_FOR: [&'static str; 16] = ["abcefg", "cf", "acdeg", "acdfg", "bcdf", "abdfg", "abdefg", "acf", "abcdefg", "abcdfg",
+    "abcdef", "bdefg", "abeg", "cdefg", "abdeg", "abde"];
+
+pub struct Day8;
+
+impl Solver for Day8 {
+    type Input = Vec<DeviceEntry>;
+    type Part1 = usize;
+    type Part2 = usize;
+
+    fn parse_lines<I: Iterator<Item=String>>(lines: I) -> anyhow::Result<Self::Input> {
+        Ok(parse_each(lines)?)
+    }
+
+    fn part1(input: &Self::Input) -> anyhow::Result<Self::Part1> {
+        Ok(solve_part_1(input))
+    }
+
+    fn part2(input: &Self::Input) -> anyhow::Result<Self::Part2> {
+        solve_part_2(input)
+    }
+}
+
+fn solve_part_1(entries: &Vec<DeviceEntry>) -> usize {
+    let easy_lengths = find_easy_lengths(&PATTERN_FOR);
+    entries.iter()
+        .map(|entry| entry.outputs.iter()
+            .filter(|output| easy_lengths.contains(&output.len()))
+            .count())
+        .sum()
+}
+
+fn solve_part_2(entries: &Vec<DeviceEntry>) -> anyhow::Result<usize> {
+    let table = SymbolTable::decimal();
+    entries.iter().map(|entry| entry.output_value(&table)).sum()
+}
+
+/// Scrambled wire to the segment it actually lights.
+pub type Mapping = HashMap<char, char>;
+
+/// The segments lit for each symbol, where a symbol's value is its position in the table.
+/// Scrambled displays use the same letters for their wires as the table uses for segments.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SymbolTable {
+    patterns: Vec<String>,
+    segments: Vec<char>
+}
+
+impl SymbolTable {
+    pub fn new(patterns: &[&str]) -> Self {
+        let patterns = patterns.iter().map(|p| sorted(p.chars())).collect::<Vec<_>>();
+        let mut segments = patterns.iter().flat_map(|p| p.chars()).collect::<Vec<_>>();
+        segments.sort();
+        segments.dedup();
+        SymbolTable {patterns, segments}
+    }
+
+    pub fn decimal() -> Self {
+        SymbolTable::new(&PATTERN_FOR)
+    }
+
+    /// Seven-segment hexadecimal, with `b` and `d` in lower case so that they differ from `8` and `0`.
+    pub fn hexadecimal() -> Self {
+        SymbolTable::new(&HEX_PATTERN_FOR)
+    }
+
+    pub fn radix(&self) -> usize {
+        self.patterns.len()
+    }
+
+    fn value_of(&self, segments: &str) -> Option<usize> {
+        self.patterns.iter().position(|p| p == segments)
+    }
+
+    /// Whether some symbol has the same number of segments as `wires` and agrees with
+    /// every assignment in `mapping` about which of them are lit.
+    fn could_show(&self, wires: &HashSet<char>, mapping: &Mapping) -> bool {
+        self.patterns.iter()
+            .filter(|p| p.len() == wires.len())
+            .any(|p| mapping.iter().all(|(wire, segment)| wires.contains(wire) == p.contains(*segment)))
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Deduction {
+    Unique(Mapping),
+    /// Holds two of the consistent mappings.
+    Ambiguous(Vec<Mapping>),
+    Impossible
+}
+
+#[derive(Debug)]
+pub struct DeviceEntry {
+    inputs: Vec<String>,
+    outputs: Vec<String>
+}
+
+impl FromStr for DeviceEntry {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (inputs, outputs) = s.split_once('|').ok_or(ParseError::expected("patterns | outputs", s))?;
+        if let Some(extra) = outputs.find('|') {
+            return Err(ParseError::expected("a single '|'", &outputs[extra..]).offset_by(inputs.len() + 1 + extra));
+        }
+        Ok(DeviceEntry {inputs: snag_put(inputs, 0)?, outputs: snag_put(outputs, inputs.len() + 1)?})
+    }
+}
+
+impl DeviceEntry {
+    pub fn output_value(&self, table: &SymbolTable) -> anyhow::Result<usize> {
+        let mapping = match self.deduce(table) {
+            Deduction::Unique(mapping) => mapping,
+            Deduction::Ambiguous(_) => bail!("More than one wiring fits {:?}", self.inputs),
+            Deduction::Impossible => bail!("No wiring fits {:?}", self.inputs)
+        };
+        let mut total = 0;
+        for output in self.outputs.iter() {
+            total *= table.radix();
+            total += decode(output.as_str(), &mapping, table).ok_or(anyhow!("Undecodable output: {}", output))?;
+        }
+        Ok(total)
+    }
+
+    pub fn deduce(&self, table: &SymbolTable) -> Deduction {
+        let mut mappings = self.mappings(table, 2);
+        match mappings.len() {
+            0 => Deduction::Impossible,
+            1 => Deduction::Unique(mappings.pop().unwrap()),
+            _ => Deduction::Ambiguous(mappings)
+        }
+    }
+
+    /// Up to `limit` wirings under which every input and output is a symbol in `table`.
+    pub fn mappings(&self, table: &SymbolTable, limit: usize) -> Vec<Mapping> {
+        let mut observed = self.inputs.iter().chain(self.outputs.iter())
+            .map(|s| sorted(s.chars()))
+            .collect::<Vec<_>>();
+        observed.sort();
+        observed.dedup();
+        let mut search = WiringSearch {
+            table,
+            observations: observed.iter().map(|s| s.chars().collect()).collect(),
+            mapping: HashMap::new(),
+            found: Vec::new(),
+            limit
+        };
+        let domains = search.initial_domains();
+        search.extend(&domains);
+        search.found
+    }
+}
+
+/// Backtracking over wire assignments, abandoning a partial mapping as soon as
+/// some observation could no longer be any symbol.
+struct WiringSearch<'a> {
+    table: &'a SymbolTable,
+    observations: Vec<HashSet<char>>,
+    mapping: Mapping,
+    found: Vec<Mapping>,
+    limit: usize
+}
+
+impl <'a> WiringSearch<'a> {
+    /// The segments each wire could light when considered on its own.
+    fn initial_domains(&self) -> HashMap<char, Vec<char>> {
+        self.table.segments.iter()
+            .map(|wire| (*wire, self.table.segments.iter()
+                .copied()
+                .filter(|segment| {
+                    let single = HashMap::from([(*wire, *segment)]);
+                    self.observations.iter().all(|o| self.table.could_show(o, &single))
+                })
+                .collect()))
+            .collect()
+    }
+
+    fn extend(&mut self, domains: &HashMap<char, Vec<char>>) {
+        if self.found.len() >= self.limit {
+            return;
+        }
+        let unassigned = domains.iter()
+            .filter(|(wire, _)| !self.mapping.contains_key(wire))
+            .map(|(wire, segments)| (*wire, segments.iter()
+                .copied()
+                .filter(|s| !self.mapping.values().any(|used| used == s))
+                .collect::<Vec<_>>()))
+            .min_by_key(|(wire, segments)| (segments.len(), *wire));
+        match unassigned {
+            None => self.found.push(self.mapping.clone()),
+            Some((wire, segments)) => {
+                for segment in segments {
+                    self.mapping.insert(wire, segment);
+                    if self.observations.iter().all(|o| self.table.could_show(o, &self.mapping)) {
+                        self.extend(domains);
+                    }
+                    self.mapping.remove(&wire);
+                }
+            }
+        }
+    }
+}
+
+fn decode(output: &str, mapping: &Mapping, table: &SymbolTable) -> Option<usize> {
+    let decoded = output.chars().map(|c| mapping.get(&c).copied()).collect::<Option<Vec<char>>>()?;
+    table.value_of(sorted(decoded.into_iter()).as_str())
+}
+
+fn sorted<I: Iterator<Item=char>>(chars: I) -> String {
+    let mut chars = chars.collect::<Vec<_>>();
+    chars.sort();
+    chars.iter().collect()
+}
+
+/// The wire patterns in `part`, which starts `offset` characters into its line.
+fn snag_put(part: &str, offset: usize) -> ParseResult<Vec<String>> {
+    let mut patterns = Vec::new();
+    let mut column = offset;
+    for token in part.split(' ') {
+        if !token.is_empty() {
+            if !token.chars().all(|c| c.is_ascii_lowercase()) {
+                return Err(ParseError::expected("wire letters", token).offset_by(column));
+            }
+            patterns.push(token.to_owned());
+        }
+        column += token.len() + 1;
+    }
+    Ok(patterns)
+}
+
+fn find_easy_lengths(strs: &[&str]) -> HashSet<usize> {
+    by_lengths(strs.iter().copied()).iter()
+        .filter(|(_, patterns)| patterns.len() == 1)
+        .map(|(len, _)| *len)
+        .collect()
+}
+
+fn by_lengths<'a, I: Iterator<Item=&'a str>>(strs: I) -> HashMap<usize, Vec<String>> {
+    let mut result = HashMap::new();
+    for s in strs {
+        match result.get_mut(&s.len()) {
+            None => {result.insert(s.len(), vec![s.to_string()]);}
+            Some(v) => {v.push(s.to_string());}
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "acedgfb cdfbe gcdfa fbcad dab cefabd cdfgeb eafb cagedb ab | cdfeb fcadb cdfeb cdbaf";
+
+    #[test]
+    fn test_example() {
+        let entry = EXAMPLE.parse::<DeviceEntry>().unwrap();
+        let expected = HashMap::from([('d', 'a'), ('e', 'b'), ('a', 'c'), ('f', 'd'), ('g', 'e'), ('b', 'f'), ('c', 'g')]);
+        assert_eq!(entry.deduce(&SymbolTable::decimal()), Deduction::Unique(expected));
+        assert_eq!(entry.output_value(&SymbolTable::decimal()).unwrap(), 5353);
+    }
+
+    #[test]
+    fn test_hexadecimal() {
+        let scramble = HashMap::from([('a', 'g'), ('b', 'a'), ('c', 'f'), ('d', 'b'), ('e', 'c'), ('f', 'e'), ('g', 'd')]);
+        let scrambled = |pattern: &str| pattern.chars().map(|c| scramble[&c]).collect::<String>();
+        let inputs = HEX_PATTERN_FOR.iter().map(|p| scrambled(p)).collect::<Vec<_>>().join(" ");
+        let outputs = [15, 0, 10, 13].map(|i| scrambled(HEX_PATTERN_FOR[i])).join(" ");
+        let entry = format!("{} | {}", inputs, outputs).parse::<DeviceEntry>().unwrap();
+        assert_eq!(entry.output_value(&SymbolTable::hexadecimal()).unwrap(), 0xF0AD);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let error = "ab cd".parse::<DeviceEntry>().unwrap_err();
+        assert_eq!(error.to_string(), "expected patterns | outputs, found 'ab cd'");
+        let error = "ab cd | a2 b".parse::<DeviceEntry>().unwrap_err();
+        assert_eq!(error.to_string(), "9: expected wire letters, found 'a2'");
+        let error = "ab | cd | ef".parse::<DeviceEntry>().unwrap_err();
+        assert_eq!(error.to_string(), "9: expected a single '|', found '| ef'");
+        let error = Day8::parse_str("ab | ab\nab ab").unwrap_err();
+        assert_eq!(error.to_string(), "2: expected patterns | outputs, found 'ab ab'");
+    }
+
+    #[test]
+    fn test_ambiguous() {
+        let entry = "ab | ab".parse::<DeviceEntry>().unwrap();
+        assert!(matches!(entry.deduce(&SymbolTable::decimal()), Deduction::Ambiguous(_)));
+        // Five wires are unconstrained, so each of the two ways to wire the 1 allows 5! more.
+        assert_eq!(entry.mappings(&SymbolTable::decimal(), usize::MAX).len(), 2 * 120);
+        assert!(entry.output_value(&SymbolTable::decimal()).is_err());
+        assert_eq!("abc ab | abd".parse::<DeviceEntry>().unwrap().deduce(&SymbolTable::decimal()), Deduction::Impossible);
+    }
+}
@@ -0,0 +1,380 @@
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::fmt::{Display, Formatter};
+use std::io;
+use std::io::Write;
+use ansi_term::{Colour, Style};
+use advent_code_lib::{Position, GridDigitWorld};
+use bare_metal_modulo::{MNum, ModNumC};
+use crate::{input_lines, Solver};
+use crate::grid::Neighbourhood;
+use crate::parse_error::digit_grid;
+
+const MIN_SAFE_HEIGHT: u8 = 9;
+const MAX_BARRIER: u8 = 10;
+const NUM_LARGEST_BASINS: usize = 3;
+const BASIN_COLOURS: [u8; 12] = [1, 2, 3, 4, 5, 6, 9, 10, 11, 12, 13, 14];
+
+pub struct Day9;
+
+impl Solver for Day9 {
+    type Input = HeightMap;
+    type Part1 = u32;
+    type Part2 = usize;
+
+    fn parse_lines<I: Iterator<Item=String>>(lines: I) -> anyhow::Result<Self::Input> {
+        HeightMap::from_iter(lines)
+    }
+
+    fn part1(input: &Self::Input) -> anyhow::Result<Self::Part1> {
+        Ok(input.risk_level_sum())
+    }
+
+    fn part2(input: &Self::Input) -> anyhow::Result<Self::Part2> {
+        Ok(input.basins().largest_basin_product(NUM_LARGEST_BASINS))
+    }
+}
+
+pub struct HeightMap {
+    heights: GridDigitWorld,
+    neighbourhood: Neighbourhood,
+    barrier: u8
+}
+
+impl HeightMap {
+    pub fn from(filename: &str) -> anyhow::Result<Self> {
+        Self::from_iter(input_lines(filename)?)
+    }
+
+    pub fn from_iter<I: Iterator<Item=String>>(lines: I) -> anyhow::Result<Self> {
+        let heights = digit_grid(lines)?;
+        Ok(HeightMap {heights, neighbourhood: Neighbourhood::Four, barrier: MIN_SAFE_HEIGHT})
+    }
+
+    pub fn with_neighbourhood(mut self, neighbourhood: Neighbourhood) -> Self {
+        self.neighbourhood = neighbourhood;
+        self
+    }
+
+    /// Cells at least this high block the flow of water.
+    pub fn with_barrier(mut self, barrier: u8) -> Self {
+        self.barrier = barrier;
+        self
+    }
+
+    fn neighbors(&self, p: Position) -> Vec<Position> {
+        self.neighbourhood.neighbors(p)
+    }
+
+    pub fn risk_level_sum(&self) -> u32 {
+        self.low_points().map(|(_, h)| h.a() as u32 + 1).sum()
+    }
+
+    fn low_points(&self) -> impl Iterator<Item=(Position,ModNumC<u8, 10>)> + '_ {
+        self.heights.position_value_iter()
+            .filter(|(p, h)| self.adjacent_location_heights(*p).all(|nh| nh > **h))
+            .map(|(p, h)| (*p, *h))
+    }
+
+    fn adjacent_location_heights<'a>(&'a self, p: &'a Position) -> impl Iterator<Item=ModNumC<u8, 10>> + 'a {
+        self.neighbors(*p).into_iter().filter_map(|n| self.heights.value(n))
+    }
+
+    fn height(&self, p: Position) -> Option<u8> {
+        self.heights.value(p).map(|h| h.a())
+    }
+
+    /// Labels every cell in one pass, from the lowest height to the highest. A plateau that
+    /// borders no lower cell starts a new basin. Otherwise each of its cells joins the basin
+    /// of the nearest lower cell, measured across the plateau, and becomes a divide if water
+    /// on it could reach more than one basin.
+    pub fn basins(&self) -> BasinMap {
+        self.basins_below(self.barrier)
+    }
+
+    fn basins_below(&self, barrier: u8) -> BasinMap {
+        let mut cells = self.heights.position_value_iter()
+            .map(|(p, h)| (h.a(), *p))
+            .filter(|(h, _)| *h < barrier)
+            .collect::<Vec<_>>();
+        cells.sort();
+        let mut labels = HashMap::new();
+        let mut num_basins = 0;
+        for (height, start) in cells {
+            if labels.contains_key(&start) {
+                continue;
+            }
+            let plateau = self.plateau(start, height);
+            let mut frontier = HashMap::new();
+            for p in plateau.iter() {
+                let drains_to = self.neighbors(*p).into_iter()
+                    .filter(|n| self.height(*n).map_or(false, |h| h < height))
+                    .map(|n| labels[&n])
+                    .collect::<BTreeSet<_>>();
+                if !drains_to.is_empty() {
+                    frontier.insert(*p, drains_to);
+                }
+            }
+            if frontier.is_empty() {
+                num_basins += 1;
+                for p in plateau {
+                    labels.insert(p, Label::Basin(num_basins - 1));
+                }
+                continue;
+            }
+            while !frontier.is_empty() {
+                for (p, drains_to) in frontier.iter() {
+                    let label = if drains_to.len() == 1 {*drains_to.iter().next().unwrap()} else {Label::Divide};
+                    labels.insert(*p, label);
+                }
+                let mut next: HashMap<Position, BTreeSet<Label>> = HashMap::new();
+                for p in frontier.keys() {
+                    for n in self.neighbors(*p).into_iter().filter(|n| plateau.contains(n) && !labels.contains_key(n)) {
+                        next.entry(n).or_default().insert(labels[p]);
+                    }
+                }
+                frontier = next;
+            }
+        }
+
+        let mut rows: Vec<Vec<(u8, Label)>> = Vec::new();
+        for p in self.heights.position_iter() {
+            if p.col == 0 {
+                rows.push(Vec::new());
+            }
+            let height = self.height(p).unwrap();
+            rows.last_mut().unwrap().push((height, labels.get(&p).copied().unwrap_or(Label::Barrier)));
+        }
+        BasinMap {rows, num_basins}
+    }
+
+    /// The basins for every barrier height at once, ignoring the configured barrier.
+    /// Each basin's children are the basins one barrier height lower whose cells it contains.
+    pub fn merge_tree(&self) -> MergeTree {
+        let mut levels: Vec<Vec<MergeNode>> = Vec::new();
+        let mut below: Option<BasinMap> = None;
+        for barrier in 0..=MAX_BARRIER {
+            let basins = self.basins_below(barrier);
+            let mut children = vec![BTreeSet::new(); basins.num_basins()];
+            if let Some(below) = below.as_ref() {
+                for (row, below_row) in basins.rows.iter().zip(below.rows.iter()) {
+                    for ((_, label), (_, below_label)) in row.iter().zip(below_row.iter()) {
+                        if let (Label::Basin(id), Label::Basin(child)) = (label, below_label) {
+                            children[*id].insert(*child);
+                        }
+                    }
+                }
+            }
+            levels.push(basins.sizes().into_iter().zip(children)
+                .map(|(size, children)| MergeNode {size, children: children.into_iter().collect()})
+                .collect());
+            below = Some(basins);
+        }
+        MergeTree {levels}
+    }
+
+    /// The cells of height `height` connected to `start` through cells of that same height.
+    fn plateau(&self, start: Position, height: u8) -> HashSet<Position> {
+        let mut plateau = HashSet::from([start]);
+        let mut pending = vec![start];
+        while let Some(p) = pending.pop() {
+            for n in self.neighbors(p) {
+                if self.height(n) == Some(height) && plateau.insert(n) {
+                    pending.push(n);
+                }
+            }
+        }
+        plateau
+    }
+}
+
+/// One basin at one barrier height.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MergeNode {
+    pub size: usize,
+    /// Indices of the basins one barrier height lower that this basin contains.
+    pub children: Vec<usize>
+}
+
+/// Level `b` holds the basins when cells of height `b` and above block the flow,
+/// indexed by basin ID as in `HeightMap::with_barrier(b).basins()`.
+pub struct MergeTree {
+    levels: Vec<Vec<MergeNode>>
+}
+
+impl MergeTree {
+    pub fn basins(&self, barrier: u8) -> &[MergeNode] {
+        &self.levels[barrier.min(MAX_BARRIER) as usize]
+    }
+
+    pub fn num_basins(&self, barrier: u8) -> usize {
+        self.basins(barrier).len()
+    }
+
+    pub fn sizes(&self, barrier: u8) -> Vec<usize> {
+        self.basins(barrier).iter().map(|node| node.size).collect()
+    }
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum Label {
+    Basin(usize),
+    /// Drains into more than one basin.
+    Divide,
+    /// Too high for water to flow through.
+    Barrier
+}
+
+impl Display for Label {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Label::Basin(id) => write!(f, "{}", id),
+            Label::Divide => write!(f, "divide"),
+            Label::Barrier => write!(f, "barrier")
+        }
+    }
+}
+
+/// The height and label of every cell, row by row.
+pub struct BasinMap {
+    rows: Vec<Vec<(u8, Label)>>,
+    num_basins: usize
+}
+
+impl BasinMap {
+    pub fn num_basins(&self) -> usize {
+        self.num_basins
+    }
+
+    pub fn label(&self, p: Position) -> Option<Label> {
+        self.rows.get(p.row as usize).and_then(|row| row.get(p.col as usize)).map(|(_, label)| *label)
+    }
+
+    /// Indexed by basin ID.
+    pub fn sizes(&self) -> Vec<usize> {
+        let mut sizes = vec![0; self.num_basins];
+        for (_, label) in self.rows.iter().flatten() {
+            if let Label::Basin(id) = label {
+                sizes[*id] += 1;
+            }
+        }
+        sizes
+    }
+
+    pub fn largest_basin_product(&self, num_largest: usize) -> usize {
+        let mut sizes = self.sizes();
+        sizes.sort_unstable_by(|a, b| b.cmp(a));
+        sizes.iter().take(num_largest).product()
+    }
+
+    pub fn write_csv<W: Write>(&self, out: &mut W) -> io::Result<()> {
+        writeln!(out, "col,row,height,basin")?;
+        for (row, cells) in self.rows.iter().enumerate() {
+            for (col, (height, label)) in cells.iter().enumerate() {
+                writeln!(out, "{},{},{},{}", col, row, height, label)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Heights coloured by basin, with divides highlighted and barriers dimmed.
+impl Display for BasinMap {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for (i, row) in self.rows.iter().enumerate() {
+            if i > 0 {writeln!(f)?;}
+            for (height, label) in row.iter() {
+                let style = match label {
+                    Label::Basin(id) => Colour::Fixed(BASIN_COLOURS[id % BASIN_COLOURS.len()]).normal(),
+                    Label::Divide => Colour::White.bold().on(Colour::Red),
+                    Label::Barrier => Style::new().dimmed()
+                };
+                write!(f, "{}", style.paint(height.to_string()))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn map(s: &str) -> HeightMap {
+        HeightMap::from_iter(s.lines().map(|line| line.to_string())).unwrap()
+    }
+
+    #[test]
+    fn test_example() {
+        let basins = HeightMap::from("ex/day9.txt").unwrap().basins();
+        assert_eq!(basins.num_basins(), 4);
+        let mut sizes = basins.sizes();
+        sizes.sort();
+        assert_eq!(sizes, vec![3, 9, 9, 14]);
+        assert_eq!(basins.largest_basin_product(3), 1134);
+        assert_eq!(basins.largest_basin_product(1), 14);
+        assert_eq!(basins.label(Position::from((2, 0))), Some(Label::Barrier));
+    }
+
+    #[test]
+    fn test_merge_tree() {
+        let example = std::fs::read_to_string("ex/day9.txt").unwrap();
+        for input in [example.as_str(), "909\n090\n909", "11913\n22923\n55555"] {
+            for neighbourhood in [Neighbourhood::Four, Neighbourhood::Eight, Neighbourhood::Hex] {
+                let height_map = || map(input).with_neighbourhood(neighbourhood);
+                let tree = height_map().merge_tree();
+                for barrier in 0..=MAX_BARRIER {
+                    let basins = height_map().with_barrier(barrier).basins();
+                    assert_eq!(tree.num_basins(barrier), basins.num_basins());
+                    assert_eq!(tree.sizes(barrier), basins.sizes());
+                    let below = tree.sizes(barrier.saturating_sub(1));
+                    for node in tree.basins(barrier).iter().filter(|_| barrier > 0) {
+                        assert!(node.size >= node.children.iter().map(|c| below[*c]).sum::<usize>());
+                    }
+                }
+            }
+        }
+        let tree = HeightMap::from("ex/day9.txt").unwrap().merge_tree();
+        for barrier in 1..=MAX_BARRIER {
+            let mut children = tree.basins(barrier).iter().flat_map(|node| node.children.clone()).collect::<Vec<_>>();
+            children.sort();
+            assert_eq!(children, (0..tree.num_basins(barrier - 1)).collect::<Vec<_>>());
+        }
+        assert!(tree.basins(9).iter().all(|node| node.size > 0));
+        assert_eq!(tree.num_basins(9), 4);
+        assert_eq!(tree.num_basins(0), 0);
+        assert_eq!(tree.num_basins(10), 4);
+        assert_eq!(tree.basins(10).iter().map(|node| node.children.clone()).collect::<Vec<_>>(), vec![vec![0], vec![1], vec![2], vec![3]]);
+
+        let tree = map("909\n090\n909").merge_tree();
+        assert_eq!(tree.num_basins(10), map("909\n090\n909").with_barrier(10).basins().num_basins());
+    }
+
+    #[test]
+    fn test_neighbourhoods() {
+        let map = || map("909\n090\n909");
+        assert_eq!(map().basins().num_basins(), 4);
+        assert_eq!(map().with_neighbourhood(Neighbourhood::Eight).basins().num_basins(), 1);
+        // Row 1 is shifted right, so its left zero touches both zeros in column 1 but its right zero neither.
+        assert_eq!(map().with_neighbourhood(Neighbourhood::Hex).basins().num_basins(), 2);
+        assert_eq!(map().with_barrier(1).basins().num_basins(), 4);
+        let unblocked = map().with_barrier(10).basins();
+        assert_eq!(unblocked.num_basins(), 4);
+        assert_eq!(unblocked.label(Position::from((0, 0))), Some(Label::Divide));
+    }
+
+    #[test]
+    fn test_plateaus_and_divides() {
+        let basins = map("11913\n22923\n55555").basins();
+        let labels = (0..5).map(|col| basins.label(Position::from((col, 1))).unwrap()).collect::<Vec<_>>();
+        assert_eq!(labels, vec![Label::Basin(0), Label::Basin(0), Label::Barrier, Label::Basin(1), Label::Basin(1)]);
+        assert_eq!(basins.label(Position::from((0, 2))), Some(Label::Basin(0)));
+        assert_eq!(basins.label(Position::from((2, 2))), Some(Label::Divide));
+        assert_eq!(basins.sizes(), vec![6, 6]);
+
+        let mut csv = Vec::new();
+        basins.write_csv(&mut csv).unwrap();
+        let csv = String::from_utf8(csv).unwrap();
+        assert_eq!(csv.lines().take(4).collect::<Vec<_>>(), vec!["col,row,height,basin", "0,0,1,0", "1,0,1,0", "2,0,9,barrier"]);
+        assert!(csv.contains("2,2,5,divide"));
+    }
+}
@@ -0,0 +1,198 @@
+use std::fmt::{Display, Formatter};
+use std::fs;
+use std::io;
+use itertools::Itertools;
+use crate::Solver;
+use crate::day12::Day12;
+use crate::day12_dfs::Day12Dfs;
+use crate::day5::{count_intersections, count_overlaps, score, segments_from_lines};
+use crate::day15::{Day15, EXPANSION_FACTOR};
+use crate::day16::Day16;
+use crate::day16_alt::Day16Alt;
+use crate::generator::find_generator;
+use crate::runner::day_inputs;
+
+pub const DEFAULT_SEEDS: u64 = 20;
+
+type Outcome = Result<[String; 2], String>;
+
+/// Two implementations of the same day that should always agree.
+#[derive(Copy, Clone)]
+pub struct Pair {
+    pub day: usize,
+    pub names: [&'static str; 2],
+    solvers: [fn(&str) -> anyhow::Result<[String; 2]>; 2],
+    /// Small enough that both sides finish quickly on every generated input.
+    pub generated_size: usize
+}
+
+pub const PAIRS: [Pair; 4] = [
+    Pair {day: 5, names: ["rasterise", "sweep"], solvers: [rasterise, sweep], generated_size: 200},
+    Pair {day: 12, names: ["day12", "day12_dfs"], solvers: [both_parts::<Day12>, both_parts::<Day12Dfs>], generated_size: 6},
+    Pair {day: 15, names: ["dijkstra", "a*"], solvers: [dijkstra, a_star], generated_size: 12},
+    Pair {day: 16, names: ["day16", "day16_alt"], solvers: [both_parts::<Day16>, both_parts::<Day16Alt>], generated_size: 40},
+];
+
+fn both_parts<S: Solver>(input: &str) -> anyhow::Result<[String; 2]> {
+    let input = S::parse_str(input)?;
+    Ok([S::part1(&input)?.to_string(), S::part2(&input)?.to_string()])
+}
+
+fn rasterise(input: &str) -> anyhow::Result<[String; 2]> {
+    let segments = segments_from_lines(input.lines().map(|s| s.to_string()))?;
+    Ok([false, true].map(|with_diagonals| score(&count_intersections(&segments, with_diagonals)).to_string()))
+}
+
+fn sweep(input: &str) -> anyhow::Result<[String; 2]> {
+    let segments = segments_from_lines(input.lines().map(|s| s.to_string()))?;
+    Ok([false, true].map(|with_diagonals| count_overlaps(&segments, with_diagonals).to_string()))
+}
+
+fn lowest_risks(input: &str, use_a_star: bool) -> anyhow::Result<[String; 2]> {
+    let map = Day15::parse_str(input)?;
+    Ok([map.lowest_risk(use_a_star)?.to_string(), map.expand(EXPANSION_FACTOR).lowest_risk(use_a_star)?.to_string()])
+}
+
+fn dijkstra(input: &str) -> anyhow::Result<[String; 2]> {
+    lowest_risks(input, false)
+}
+
+fn a_star(input: &str) -> anyhow::Result<[String; 2]> {
+    lowest_risks(input, true)
+}
+
+#[derive(Clone, Debug)]
+pub struct Divergence {
+    pub names: [&'static str; 2],
+    /// Where the original diverging input came from: a filename, or a generator seed.
+    pub source: String,
+    /// The smallest part of that input on which the two still disagree.
+    pub input: String,
+    pub outcomes: [Outcome; 2]
+}
+
+impl Display for Divergence {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "{} and {} disagree on {}, minimized to:", self.names[0], self.names[1], self.source)?;
+        writeln!(f, "{}", self.input)?;
+        for (name, outcome) in self.names.iter().zip(self.outcomes.iter()) {
+            match outcome {
+                Ok([part1, part2]) => writeln!(f, "{}: part 1 = {}, part 2 = {}", name, part1, part2)?,
+                Err(e) => writeln!(f, "{}: error: {}", name, e)?
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Pair {
+    fn outcomes(&self, input: &str) -> [Outcome; 2] {
+        self.solvers.map(|solve| solve(input).map_err(|e| e.to_string()))
+    }
+
+    /// Inputs both sides reject count as agreement, since error messages are free to differ.
+    fn disagree(outcomes: &[Outcome; 2]) -> bool {
+        match outcomes {
+            [Ok(a), Ok(b)] => a != b,
+            [Ok(_), Err(_)] | [Err(_), Ok(_)] => true,
+            [Err(_), Err(_)] => false
+        }
+    }
+
+    /// Runs both sides on each `(source, input)` and reports the first disagreement.
+    pub fn first_divergence<I: Iterator<Item=(String, String)>>(&self, mut inputs: I) -> Option<Divergence> {
+        inputs
+            .find(|(_, input)| Pair::disagree(&self.outcomes(input.as_str())))
+            .map(|(source, input)| {
+                let input = self.minimized(input.as_str());
+                Divergence {names: self.names, source, outcomes: self.outcomes(input.as_str()), input}
+            })
+    }
+
+    /// Removes lines one at a time for as long as the two sides still disagree.
+    pub fn minimized(&self, input: &str) -> String {
+        let mut lines = input.lines().map(|s| s.to_string()).collect_vec();
+        let mut i = 0;
+        while i < lines.len() {
+            let mut candidate = lines.clone();
+            candidate.remove(i);
+            if Pair::disagree(&self.outcomes(candidate.join("\n").as_str())) {
+                lines = candidate;
+            } else {
+                i += 1;
+            }
+        }
+        lines.join("\n")
+    }
+
+    pub fn generated_inputs(&self, seeds: u64) -> impl Iterator<Item=(String, String)> + '_ {
+        let generator = find_generator(self.day).unwrap();
+        (0..seeds).map(move |seed| (format!("seed {} size {}", seed, self.generated_size),
+                                    generator.generate(seed, self.generated_size).input))
+    }
+
+    /// The contents of every `dir/dayN*.txt`, e.g. `ex/day12a.txt` but not `ex/day1.txt`.
+    pub fn recorded_inputs(&self, dir: &str) -> io::Result<Vec<(String, String)>> {
+        day_inputs(dir, self.day)?.into_iter()
+            .map(|filename| fs::read_to_string(filename.as_str()).map(|input| (filename, input)))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SEEDS: u64 = 10;
+
+    fn assert_agree(pair: &Pair) {
+        let recorded = pair.recorded_inputs("ex").unwrap();
+        if let Some(divergence) = pair.first_divergence(recorded.into_iter().chain(pair.generated_inputs(SEEDS))) {
+            panic!("{}", divergence);
+        }
+    }
+
+    #[test]
+    fn test_day5() {
+        assert_agree(&PAIRS[0]);
+    }
+
+    #[test]
+    fn test_day5_off_slope() {
+        let input = "0,0 -> 3,1\n2,2 -> 2,5\n5,0 -> 0,1\n4,1 -> 1,4";
+        assert!(PAIRS[0].first_divergence([("off slope".to_string(), input.to_string())].into_iter()).is_none());
+    }
+
+    #[test]
+    fn test_day12() {
+        assert_agree(&PAIRS[1]);
+    }
+
+    #[test]
+    fn test_day15() {
+        assert_agree(&PAIRS[2]);
+    }
+
+    #[test]
+    fn test_day16() {
+        assert_agree(&PAIRS[3]);
+    }
+
+    fn count_lines(input: &str) -> anyhow::Result<[String; 2]> {
+        Ok([input.lines().count().to_string(), String::new()])
+    }
+
+    fn count_lines_but_x(input: &str) -> anyhow::Result<[String; 2]> {
+        Ok([input.lines().filter(|line| *line != "x").count().to_string(), String::new()])
+    }
+
+    #[test]
+    fn test_minimized() {
+        let pair = Pair {day: 0, names: ["all", "but x"], solvers: [count_lines, count_lines_but_x], generated_size: 0};
+        let inputs = [("agrees", "a\nb"), ("disagrees", "a\nx\nb\nx")].map(|(s, i)| (s.to_string(), i.to_string()));
+        let divergence = pair.first_divergence(inputs.into_iter()).unwrap();
+        assert_eq!(divergence.source, "disagrees");
+        assert_eq!(divergence.input, "x");
+        assert_eq!(divergence.outcomes[0].as_ref().unwrap()[0], "1");
+    }
+}
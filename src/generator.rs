@@ -0,0 +1,378 @@
+use std::collections::{BTreeSet, HashSet};
+use std::fmt::Write;
+use anyhow::anyhow;
+use itertools::Itertools;
+use num::BigUint;
+use rand::{Rng, SeedableRng};
+use rand::seq::SliceRandom;
+use rand_chacha::ChaCha8Rng;
+use crate::day19::Point3;
+
+pub const DEFAULT_SEED: u64 = 2021;
+
+/// A random puzzle input. When the generator knows the answers by construction, they come along too.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Generated {
+    pub input: String,
+    pub expected: [Option<String>; 2]
+}
+
+impl Generated {
+    fn unsolved(input: String) -> Self {
+        Generated {input, expected: [None, None]}
+    }
+}
+
+#[derive(Copy, Clone)]
+pub struct Generator {
+    pub day: usize,
+    /// What `size` counts for this day, for the usage message.
+    pub size_of: &'static str,
+    pub default_size: usize,
+    generate: fn(&mut ChaCha8Rng, usize) -> Generated
+}
+
+impl Generator {
+    /// The same `seed` and `size` always produce the same input.
+    pub fn generate(&self, seed: u64, size: usize) -> Generated {
+        (self.generate)(&mut ChaCha8Rng::seed_from_u64(seed), size)
+    }
+}
+
+pub const GENERATORS: [Generator; 7] = [
+    Generator {day: 5, size_of: "line segments", default_size: 500, generate: vent_lines},
+    Generator {day: 12, size_of: "small caves", default_size: 8, generate: cave_graph},
+    Generator {day: 15, size_of: "grid side", default_size: 100, generate: risk_grid},
+    Generator {day: 16, size_of: "packets", default_size: 60, generate: transmission},
+    Generator {day: 19, size_of: "scanners", default_size: 5, generate: scanner_reports},
+    Generator {day: 22, size_of: "reboot steps", default_size: 420, generate: reboot_steps},
+    Generator {day: 25, size_of: "grid side", default_size: 137, generate: sea_cucumbers},
+];
+
+pub fn find_generator(day: usize) -> anyhow::Result<Generator> {
+    GENERATORS.iter()
+        .find(|g| g.day == day)
+        .copied()
+        .ok_or(anyhow!("No input generator for day {}; days with generators: {}", day, GENERATORS.iter().map(|g| g.day).join(", ")))
+}
+
+const VENT_GRID: isize = 1000;
+
+/// Horizontal, vertical and 45-degree segments, as the puzzle promises.
+fn vent_lines(rng: &mut ChaCha8Rng, size: usize) -> Generated {
+    let mut input = String::new();
+    for _ in 0..size {
+        let (dx, dy) = *[(1, 0), (0, 1), (1, 1), (1, -1)].choose(rng).unwrap();
+        let length = rng.gen_range(1..VENT_GRID / 4);
+        let mut start = |d: isize| match d {
+            1 => rng.gen_range(0..VENT_GRID - length),
+            -1 => rng.gen_range(length..VENT_GRID),
+            _ => rng.gen_range(0..VENT_GRID)
+        };
+        let (x1, y1) = (start(dx), start(dy));
+        let (x2, y2) = (x1 + dx * length, y1 + dy * length);
+        let ((x1, y1), (x2, y2)) = if rng.gen_bool(0.5) {((x1, y1), (x2, y2))} else {((x2, y2), (x1, y1))};
+        writeln!(input, "{},{} -> {},{}", x1, y1, x2, y2).unwrap();
+    }
+    Generated::unsolved(input)
+}
+
+/// A connected cave system. Big caves never touch each other, or there would be infinitely many paths.
+fn cave_graph(rng: &mut ChaCha8Rng, size: usize) -> Generated {
+    let mut names = HashSet::new();
+    let mut caves = Vec::new();
+    for i in 0..size + size / 2 {
+        let big = i >= size;
+        let name = loop {
+            let name: String = (0..2).map(|_| rng.gen_range(b'a'..=b'z') as char).collect();
+            let name = if big {name.to_uppercase()} else {name};
+            if names.insert(name.clone()) {break name;}
+        };
+        caves.push(name);
+    }
+    caves.shuffle(rng);
+    caves.insert(0, "start".to_string());
+    caves.push("end".to_string());
+
+    let is_big = |cave: &String| cave.chars().all(|c| c.is_ascii_uppercase());
+    let allowed = |a: &String, b: &String| a != b && !(is_big(a) && is_big(b));
+    let mut edges = BTreeSet::new();
+    for i in 1..caves.len() {
+        let partner = loop {
+            let j = rng.gen_range(0..i);
+            if allowed(&caves[i], &caves[j]) {break j;}
+        };
+        edges.insert((partner, i));
+    }
+    for _ in 0..size / 2 {
+        let (a, b) = (rng.gen_range(0..caves.len()), rng.gen_range(0..caves.len()));
+        if allowed(&caves[a], &caves[b]) {
+            edges.insert((a.min(b), a.max(b)));
+        }
+    }
+
+    let mut input = String::new();
+    for (a, b) in edges {
+        writeln!(input, "{}-{}", caves[a], caves[b]).unwrap();
+    }
+    Generated::unsolved(input)
+}
+
+fn risk_grid(rng: &mut ChaCha8Rng, size: usize) -> Generated {
+    let mut input = String::new();
+    for _ in 0..size {
+        let row: String = (0..size).map(|_| char::from_digit(rng.gen_range(1..=9), 10).unwrap()).collect();
+        writeln!(input, "{}", row).unwrap();
+    }
+    Generated::unsolved(input)
+}
+
+const LITERAL_TYPE: u64 = 4;
+const MAX_SUB_PACKET_BITS: usize = (1 << 15) - 1;
+
+/// A hexadecimal BITS transmission of about `size` packets. The version sum and value
+/// are computed while encoding, so both answers are known.
+fn transmission(rng: &mut ChaCha8Rng, size: usize) -> Generated {
+    let mut bits = String::new();
+    let (version_sum, value) = encode_packet(rng, size.max(1), &mut bits);
+    let padded = bits.len().div_ceil(4) * 4;
+    while bits.len() < padded {
+        bits.push('0');
+    }
+    let input = bits.as_bytes()
+        .chunks(4)
+        .map(|nibble| {
+            let nibble = std::str::from_utf8(nibble).unwrap();
+            char::from_digit(u32::from_str_radix(nibble, 2).unwrap(), 16).unwrap().to_ascii_uppercase()
+        })
+        .collect::<String>();
+    Generated {input: format!("{}\n", input), expected: [Some(version_sum.to_string()), Some(value.to_string())]}
+}
+
+/// Appends one packet containing `budget` packets in all, returning its version sum and value.
+fn encode_packet(rng: &mut ChaCha8Rng, budget: usize, bits: &mut String) -> (u64, BigUint) {
+    let version = rng.gen_range(0..8);
+    push_bits(bits, version, 3);
+    if budget == 1 {
+        push_bits(bits, LITERAL_TYPE, 3);
+        let value_bits = rng.gen_range(1..=32);
+        let value: u64 = rng.gen_range(0..1 << value_bits);
+        let groups = (64 - value.leading_zeros() as usize).div_ceil(4).max(1);
+        for g in (0..groups).rev() {
+            bits.push(if g > 0 {'1'} else {'0'});
+            push_bits(bits, value >> (4 * g), 4);
+        }
+        return (version, BigUint::from(value));
+    }
+
+    let op_type = if budget == 2 {rng.gen_range(0..4)} else {*[0, 1, 2, 3, 5, 6, 7].choose(rng).unwrap()};
+    push_bits(bits, op_type, 3);
+    let num_children = if op_type > LITERAL_TYPE {2} else {rng.gen_range(1..=3.min(budget - 1))};
+    let mut remaining = budget - 1;
+    let mut children = String::new();
+    let mut version_sum = version;
+    let mut values = Vec::new();
+    for i in 0..num_children {
+        let child_budget = if i + 1 == num_children {remaining} else {rng.gen_range(1..=remaining - (num_children - 1 - i))};
+        remaining -= child_budget;
+        let (child_versions, child_value) = encode_packet(rng, child_budget, &mut children);
+        version_sum += child_versions;
+        values.push(child_value);
+    }
+    if children.len() <= MAX_SUB_PACKET_BITS && rng.gen_bool(0.5) {
+        bits.push('0');
+        push_bits(bits, children.len() as u64, 15);
+    } else {
+        bits.push('1');
+        push_bits(bits, num_children as u64, 11);
+    }
+    bits.push_str(children.as_str());
+
+    let compare = |holds: bool| BigUint::from(holds as u8);
+    let value = match op_type {
+        0 => values.into_iter().sum(),
+        1 => values.into_iter().product(),
+        2 => values.into_iter().min().unwrap(),
+        3 => values.into_iter().max().unwrap(),
+        5 => compare(values[0] > values[1]),
+        6 => compare(values[0] < values[1]),
+        _ => compare(values[0] == values[1])
+    };
+    (version_sum, value)
+}
+
+fn push_bits(bits: &mut String, value: u64, width: usize) {
+    for i in (0..width).rev() {
+        bits.push(if (value >> i) & 1 == 1 {'1'} else {'0'});
+    }
+}
+
+const SCANNER_RANGE: isize = 1000;
+const SCANNER_SPREAD: isize = 1200;
+const SHARED_BEACONS: usize = 12;
+const BEACONS_PER_SCANNER: usize = 15;
+
+/// Each scanner after the first is placed near an earlier one and shares at least 12
+/// beacons with it, then reports what it sees in a random orientation. Since the true
+/// layout is known, so are the total beacon count and the largest scanner distance.
+fn scanner_reports(rng: &mut ChaCha8Rng, size: usize) -> Generated {
+    let mut scanners = vec![Point3 {x: 0, y: 0, z: 0}];
+    let mut beacons = HashSet::new();
+    add_beacons(rng, &mut beacons, &[scanners[0]], BEACONS_PER_SCANNER);
+    for _ in 1..size.max(1) {
+        let neighbor = *scanners.choose(rng).unwrap();
+        let mut offset = || rng.gen_range(-SCANNER_SPREAD..=SCANNER_SPREAD);
+        let scanner = neighbor + Point3 {x: offset(), y: offset(), z: offset()};
+        add_beacons(rng, &mut beacons, &[neighbor, scanner], SHARED_BEACONS);
+        add_beacons(rng, &mut beacons, &[scanner], BEACONS_PER_SCANNER - SHARED_BEACONS);
+        scanners.push(scanner);
+    }
+
+    let mut input = String::new();
+    for (i, scanner) in scanners.iter().enumerate() {
+        let orientation = if i == 0 {0} else {rng.gen_range(0..24)};
+        let mut seen = beacons.iter()
+            .filter(|b| scanner.within(**b, SCANNER_RANGE))
+            .map(|b| (*b - *scanner).orientations()[orientation])
+            .collect_vec();
+        seen.sort_by_key(|p| p.to_string());
+        seen.shuffle(rng);
+        writeln!(input, "--- scanner {} ---", i).unwrap();
+        for beacon in seen {
+            writeln!(input, "{}", beacon).unwrap();
+        }
+        if i + 1 < scanners.len() {
+            writeln!(input).unwrap();
+        }
+    }
+    let max_distance = scanners.iter()
+        .tuple_combinations()
+        .map(|(a, b)| a.manhattan_distance(*b))
+        .max()
+        .unwrap_or(0);
+    Generated {input, expected: [Some(beacons.len().to_string()), Some(max_distance.to_string())]}
+}
+
+/// Adds `count` new beacons within range of every scanner in `seen_by`.
+fn add_beacons(rng: &mut ChaCha8Rng, beacons: &mut HashSet<Point3>, seen_by: &[Point3], count: usize) {
+    let bounds = |axis: fn(&Point3) -> isize| {
+        let low = seen_by.iter().map(|s| axis(s) - SCANNER_RANGE).max().unwrap();
+        let high = seen_by.iter().map(|s| axis(s) + SCANNER_RANGE).min().unwrap();
+        low..=high
+    };
+    let (xs, ys, zs) = (bounds(|p| p.x), bounds(|p| p.y), bounds(|p| p.z));
+    let target = beacons.len() + count;
+    while beacons.len() < target {
+        beacons.insert(Point3 {x: rng.gen_range(xs.clone()), y: rng.gen_range(ys.clone()), z: rng.gen_range(zs.clone())});
+    }
+}
+
+const INIT_REGION: isize = 50;
+const REBOOT_REGION: isize = 100000;
+
+/// The first half of the steps stay in the initialization region, like the real inputs.
+fn reboot_steps(rng: &mut ChaCha8Rng, size: usize) -> Generated {
+    let mut input = String::new();
+    for i in 0..size {
+        let (region, max_width) = if i < size / 2 {(INIT_REGION, INIT_REGION)} else {(REBOOT_REGION, REBOOT_REGION / 2)};
+        let action = if i == 0 || rng.gen_bool(0.6) {"on"} else {"off"};
+        let mut range = || {
+            let start = rng.gen_range(-region..=region);
+            let end = (start + rng.gen_range(0..=max_width)).min(region);
+            format!("{}..{}", start, end)
+        };
+        writeln!(input, "{} x={},y={},z={}", action, range(), range(), range()).unwrap();
+    }
+    Generated::unsolved(input)
+}
+
+/// A full row of `>` and a full column of `v` block every herd for good,
+/// so each cucumber can only move a bounded distance and the herds always stop.
+fn sea_cucumbers(rng: &mut ChaCha8Rng, size: usize) -> Generated {
+    let size = size.max(2);
+    let (wall_row, wall_col) = (rng.gen_range(0..size), rng.gen_range(0..size));
+    let mut input = String::new();
+    for row in 0..size {
+        let line: String = (0..size).map(|col| {
+            if col == wall_col {
+                'v'
+            } else if row == wall_row {
+                '>'
+            } else {
+                *['>', 'v', '.', '.'].choose(rng).unwrap()
+            }
+        }).collect();
+        writeln!(input, "{}", line).unwrap();
+    }
+    Generated::unsolved(input)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Solver;
+    use crate::day12::Day12;
+    use crate::day15::Day15;
+    use crate::day16::Day16;
+    use crate::day19::Day19;
+    use crate::day22::Day22;
+    use crate::day25::Day25;
+    use super::*;
+
+    fn generated(day: usize, size: usize) -> Generated {
+        find_generator(day).unwrap().generate(DEFAULT_SEED, size)
+    }
+
+    #[test]
+    fn test_reproducible() {
+        for generator in GENERATORS.iter() {
+            let size = generator.default_size.min(20);
+            assert_eq!(generator.generate(7, size), generator.generate(7, size));
+            assert_ne!(generator.generate(7, size).input, generator.generate(8, size).input);
+        }
+    }
+
+    #[test]
+    fn test_big_caves_apart() {
+        let input = Day12::parse_str(generated(12, 10).input.as_str()).unwrap();
+        assert!(Day12::part1(&input).unwrap() > 0);
+        for line in generated(12, 10).input.lines() {
+            let (a, b) = line.split_once('-').unwrap();
+            assert!(a.to_lowercase() == a || b.to_lowercase() == b);
+        }
+    }
+
+    #[test]
+    fn test_parse() {
+        Day15::parse_str(generated(15, 10).input.as_str()).unwrap();
+        Day22::parse_str(generated(22, 10).input.as_str()).unwrap();
+        Day25::parse_str(generated(25, 10).input.as_str()).unwrap();
+    }
+
+    #[test]
+    fn test_transmission() {
+        for seed in 0..10 {
+            let generated = find_generator(16).unwrap().generate(seed, 30);
+            let packet = Day16::parse_str(generated.input.as_str()).unwrap();
+            assert_eq!(Some(Day16::part1(&packet).unwrap().to_string()), generated.expected[0]);
+            assert_eq!(Some(Day16::part2(&packet).unwrap().to_string()), generated.expected[1]);
+        }
+    }
+
+    #[test]
+    fn test_scanner_overlap() {
+        let generated = generated(19, 6);
+        let scanners = generated.input.split("\n\n").collect_vec();
+        assert_eq!(scanners.len(), 6);
+        assert!(scanners.iter().all(|s| s.lines().count() > SHARED_BEACONS));
+    }
+
+    #[test]
+    fn test_scanner_alignment() {
+        for seed in 0..5 {
+            let generated = find_generator(19).unwrap().generate(seed, 6);
+            let scanners = Day19::parse_str(generated.input.as_str()).unwrap();
+            assert_eq!(Some(Day19::part1(&scanners).unwrap().to_string()), generated.expected[0]);
+            assert_eq!(Some(Day19::part2(&scanners).unwrap().to_string()), generated.expected[1]);
+        }
+    }
+}
@@ -0,0 +1,68 @@
+use std::str::FromStr;
+use advent_code_lib::Position;
+use crate::parse_error::ParseError;
+
+/// Which cells of a grid count as adjacent.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Neighbourhood {
+    Four,
+    Eight,
+    /// Rows of hexagons, with odd rows shifted half a cell to the right.
+    Hex
+}
+
+const FOUR: [(isize, isize); 4] = [(0, -1), (1, 0), (0, 1), (-1, 0)];
+const EIGHT: [(isize, isize); 8] = [(0, -1), (1, -1), (1, 0), (1, 1), (0, 1), (-1, 1), (-1, 0), (-1, -1)];
+const HEX_EVEN_ROW: [(isize, isize); 6] = [(-1, -1), (0, -1), (1, 0), (0, 1), (-1, 1), (-1, 0)];
+const HEX_ODD_ROW: [(isize, isize); 6] = [(0, -1), (1, -1), (1, 0), (1, 1), (0, 1), (-1, 0)];
+
+impl Neighbourhood {
+    pub fn neighbors(&self, p: Position) -> Vec<Position> {
+        let offsets: &[(isize, isize)] = match self {
+            Neighbourhood::Four => &FOUR,
+            Neighbourhood::Eight => &EIGHT,
+            Neighbourhood::Hex if p.row % 2 == 0 => &HEX_EVEN_ROW,
+            Neighbourhood::Hex => &HEX_ODD_ROW
+        };
+        offsets.iter().map(|(dc, dr)| Position::from((p.col + dc, p.row + dr))).collect()
+    }
+
+    /// Whether a grid with `height` rows can wrap its bottom edge round to its top.
+    /// Hex rows alternate their offsets, so only an even number of rows lines up.
+    pub fn wraps_vertically(&self, height: usize) -> bool {
+        *self != Neighbourhood::Hex || height % 2 == 0
+    }
+}
+
+impl FromStr for Neighbourhood {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "4" => Ok(Neighbourhood::Four),
+            "8" => Ok(Neighbourhood::Eight),
+            "hex" => Ok(Neighbourhood::Hex),
+            other => Err(ParseError::expected("a neighbourhood of 4, 8 or hex", other))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hex_offsets_are_symmetric() {
+        for p in [Position::from((3, 4)), Position::from((3, 5))] {
+            for n in Neighbourhood::Hex.neighbors(p) {
+                assert!(Neighbourhood::Hex.neighbors(n).contains(&p), "{} and {}", p, n);
+            }
+        }
+    }
+
+    #[test]
+    fn test_parse() {
+        assert_eq!("hex".parse::<Neighbourhood>().unwrap(), Neighbourhood::Hex);
+        assert_eq!("6".parse::<Neighbourhood>().unwrap_err().to_string(), "expected a neighbourhood of 4, 8 or hex, found '6'");
+    }
+}
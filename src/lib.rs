@@ -0,0 +1,103 @@
+use std::fmt::Display;
+use std::io;
+use std::io::BufRead;
+use std::sync::OnceLock;
+use advent_code_lib::all_lines;
+use crate::parse_error::ParseError;
+
+pub mod day1;
+pub mod day2;
+pub mod day3;
+pub mod day4;
+pub mod day5;
+pub mod day6;
+pub mod day7;
+pub mod day8;
+pub mod day9;
+pub mod day10;
+pub mod day11;
+pub mod day12;
+pub mod day12_dfs;
+pub mod day13;
+pub mod day14;
+pub mod day15;
+pub mod day16;
+pub mod day16_alt;
+pub mod day17;
+pub mod day18;
+pub mod day19;
+pub mod day20;
+pub mod day21;
+pub mod day22;
+pub mod day23;
+pub mod day24;
+pub mod day25;
+pub mod answers;
+pub mod bench;
+pub mod differential;
+pub mod generator;
+pub mod grid;
+pub mod parse_error;
+pub mod runner;
+
+/// Passing this as a filename reads the puzzle input from standard input.
+pub const STDIN: &'static str = "-";
+
+/// A puzzle solution: parse the input once, then answer each part from the parsed input.
+pub trait Solver {
+    type Input;
+    type Part1: Display;
+    type Part2: Display;
+
+    fn parse_lines<I: Iterator<Item=String>>(lines: I) -> anyhow::Result<Self::Input>;
+
+    fn parse(filename: &str) -> anyhow::Result<Self::Input> {
+        Self::parse_lines(input_lines(filename)?).map_err(|e| match e.downcast::<ParseError>() {
+            Ok(e) => e.in_file(filename).into(),
+            Err(e) => e
+        })
+    }
+
+    fn parse_str(input: &str) -> anyhow::Result<Self::Input> {
+        Self::parse_lines(input.lines().map(|line| line.to_string()))
+    }
+
+    fn parse_reader<R: BufRead>(reader: R) -> anyhow::Result<Self::Input> {
+        Self::parse_lines(reader.lines().collect::<io::Result<Vec<_>>>()?.into_iter())
+    }
+
+    fn part1(input: &Self::Input) -> anyhow::Result<Self::Part1>;
+
+    fn part2(input: &Self::Input) -> anyhow::Result<Self::Part2>;
+}
+
+pub fn solve<S: Solver>(filename: &str) -> anyhow::Result<()> {
+    let input = S::parse(filename)?;
+    println!("Part 1: {}", S::part1(&input)?);
+    println!("Part 2: {}", S::part2(&input)?);
+    Ok(())
+}
+
+pub fn solve_part1<S: Solver>(filename: &str) -> anyhow::Result<()> {
+    let input = S::parse(filename)?;
+    println!("Part 1: {}", S::part1(&input)?);
+    Ok(())
+}
+
+/// The lines of `filename`, or of standard input when `filename` is `-`.
+/// Standard input is read once and kept, so that each part can parse it again.
+pub fn input_lines(filename: &str) -> io::Result<Box<dyn Iterator<Item=String>>> {
+    static STDIN_LINES: OnceLock<Vec<String>> = OnceLock::new();
+    if filename == STDIN {
+        let lines = match STDIN_LINES.get() {
+            Some(lines) => lines,
+            None => {
+                let lines = io::stdin().lines().collect::<io::Result<Vec<_>>>()?;
+                STDIN_LINES.get_or_init(|| lines)
+            }
+        };
+        Ok(Box::new(lines.iter().cloned()))
+    } else {
+        Ok(Box::new(all_lines(filename)?))
+    }
+}
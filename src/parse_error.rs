@@ -0,0 +1,189 @@
+use std::fmt::{Display, Formatter};
+use std::io;
+use std::str::FromStr;
+use advent_code_lib::GridDigitWorld;
+use crate::input_lines;
+
+pub type ParseResult<T> = Result<T, ParseError>;
+
+/// A malformed puzzle input, located as precisely as the parser that found it knows how.
+/// Line and column numbers start at 1.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ParseError {
+    pub filename: Option<String>,
+    pub line: Option<usize>,
+    pub column: Option<usize>,
+    pub kind: ParseErrorKind
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ParseErrorKind {
+    Expected {expected: String, found: String},
+    EndOfInput {expected: String},
+    Io(String)
+}
+
+impl ParseError {
+    pub fn expected(expected: &str, found: &str) -> Self {
+        Self::new(ParseErrorKind::Expected {expected: expected.to_string(), found: found.to_string()})
+    }
+
+    pub fn end_of_input(expected: &str) -> Self {
+        Self::new(ParseErrorKind::EndOfInput {expected: expected.to_string()})
+    }
+
+    fn new(kind: ParseErrorKind) -> Self {
+        ParseError {filename: None, line: None, column: None, kind}
+    }
+
+    pub fn at_column(mut self, column: usize) -> Self {
+        self.column = Some(column);
+        self
+    }
+
+    /// For errors from parsing a token that starts `offset` characters into its line.
+    pub fn offset_by(mut self, offset: usize) -> Self {
+        self.column = Some(self.column.unwrap_or(1) + offset);
+        self
+    }
+
+    pub fn at_line(mut self, line: usize) -> Self {
+        self.line = Some(line);
+        self
+    }
+
+    pub fn in_file(mut self, filename: &str) -> Self {
+        self.filename = Some(filename.to_string());
+        self
+    }
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for location in [self.filename.clone(), self.line.map(|n| n.to_string()), self.column.map(|n| n.to_string())] {
+            if let Some(location) = location {
+                write!(f, "{}:", location)?;
+            }
+        }
+        if self.filename.is_some() || self.line.is_some() || self.column.is_some() {
+            write!(f, " ")?;
+        }
+        match &self.kind {
+            ParseErrorKind::Expected {expected, found} => write!(f, "expected {}, found '{}'", expected, found),
+            ParseErrorKind::EndOfInput {expected} => write!(f, "expected {}, found end of input", expected),
+            ParseErrorKind::Io(e) => write!(f, "{}", e)
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+impl From<io::Error> for ParseError {
+    fn from(e: io::Error) -> Self {
+        Self::new(ParseErrorKind::Io(e.to_string()))
+    }
+}
+
+impl From<ParseError> for io::Error {
+    fn from(e: ParseError) -> Self {
+        io::Error::new(io::ErrorKind::InvalidData, e.to_string())
+    }
+}
+
+pub fn parse_number<N: FromStr>(s: &str) -> ParseResult<N> {
+    s.parse().map_err(|_| ParseError::expected("a number", s))
+}
+
+pub fn next_line<I: Iterator<Item=String>>(lines: &mut I, expected: &str) -> ParseResult<String> {
+    lines.next().ok_or(ParseError::end_of_input(expected))
+}
+
+/// Parses the `separator`-separated numbers in `line`; repeated separators are skipped.
+pub fn numbers_in<N: FromStr>(line: &str, separator: char) -> ParseResult<Vec<N>> {
+    let mut numbers = Vec::new();
+    let mut offset = 0;
+    for token in line.split(separator) {
+        if !token.is_empty() {
+            numbers.push(parse_number(token).map_err(|e| e.offset_by(offset))?);
+        }
+        offset += token.len() + 1;
+    }
+    Ok(numbers)
+}
+
+/// Parses the comma-separated numbers on the first line.
+pub fn first_line_numbers<N: FromStr, I: Iterator<Item=String>>(lines: &mut I) -> ParseResult<Vec<N>> {
+    next_line(lines, "a line of numbers")
+        .and_then(|line| numbers_in(line.as_str(), ','))
+        .map_err(|e| e.at_line(1))
+}
+
+/// Parses one number per line.
+pub fn numbers_each<N: FromStr, I: Iterator<Item=String>>(lines: I) -> ParseResult<Vec<N>> {
+    lines.enumerate()
+        .map(|(i, line)| parse_number(line.as_str()).map_err(|e| e.at_line(i + 1)))
+        .collect()
+}
+
+/// Parses one `T` per line, tagging any error with its line.
+pub fn parse_each<T: FromStr<Err=ParseError>, I: Iterator<Item=String>>(lines: I) -> ParseResult<Vec<T>> {
+    lines.enumerate()
+        .map(|(i, line)| line.parse().map_err(|e: ParseError| e.at_line(i + 1)))
+        .collect()
+}
+
+/// Parses a rectangular grid of single digits, one row per line.
+pub fn digit_grid<I: Iterator<Item=String>>(lines: I) -> ParseResult<GridDigitWorld> {
+    let mut rows: Vec<String> = Vec::new();
+    for (i, line) in lines.enumerate() {
+        if let Some((col, c)) = line.chars().enumerate().find(|(_, c)| !c.is_ascii_digit()) {
+            return Err(ParseError::expected("a digit", c.to_string().as_str()).at_column(col + 1).at_line(i + 1));
+        }
+        if let Some(first) = rows.first() {
+            if line.len() != first.len() {
+                let expected = format!("a row of {} digits", first.len());
+                return Err(ParseError::expected(expected.as_str(), line.as_str()).at_line(i + 1));
+            }
+        }
+        rows.push(line);
+    }
+    if rows.is_empty() {
+        return Err(ParseError::end_of_input("a row of digits"));
+    }
+    rows.join("\n").parse::<GridDigitWorld>().map_err(|e| ParseError::expected("a grid of digits", e.to_string().as_str()))
+}
+
+pub fn parse_lines<T: FromStr<Err=ParseError>>(filename: &str) -> ParseResult<Vec<T>> {
+    parse_each(input_lines(filename)?).map_err(|e| e.in_file(filename))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_display() {
+        let error = ParseError::expected("'on' or 'off'", "of");
+        assert_eq!(error.to_string(), "expected 'on' or 'off', found 'of'");
+        let error = error.at_line(17).in_file("in/day22.txt");
+        assert_eq!(error.to_string(), "in/day22.txt:17: expected 'on' or 'off', found 'of'");
+        let error = ParseError::end_of_input("a number").at_column(3).offset_by(4).at_line(2);
+        assert_eq!(error.to_string(), "2:7: expected a number, found end of input");
+    }
+
+    #[test]
+    fn test_numbers_in() {
+        assert_eq!(numbers_in::<usize>(" 8  2 23", ' ').unwrap(), vec![8, 2, 23]);
+        assert_eq!(numbers_in::<usize>("7,4,9", ',').unwrap(), vec![7, 4, 9]);
+        assert_eq!(numbers_in::<usize>("7,4,x9", ',').unwrap_err().column, Some(5));
+    }
+
+    #[test]
+    fn test_digit_grid() {
+        let grid = |text: &str| digit_grid(text.lines().map(|line| line.to_string()));
+        assert_eq!(grid("123\n456").unwrap().len(), 6);
+        assert_eq!(grid("123\n4a6").unwrap_err().to_string(), "2:2: expected a digit, found 'a'");
+        assert_eq!(grid("123\n45").unwrap_err().to_string(), "2: expected a row of 3 digits, found '45'");
+        assert!(grid("").is_err());
+    }
+}
@@ -0,0 +1,190 @@
+use std::any::type_name;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::time::Instant;
+use anyhow::{anyhow, bail};
+use serde::Serialize;
+use crate::Solver;
+use crate::bench::{Sample, time_parts};
+use crate::day1::Day1;
+use crate::day2::Day2;
+use crate::day3::Day3;
+use crate::day4::Day4;
+use crate::day5::Day5;
+use crate::day6::Day6;
+use crate::day7::Day7;
+use crate::day8::Day8;
+use crate::day9::Day9;
+use crate::day10::Day10;
+use crate::day11::Day11;
+use crate::day12::Day12;
+use crate::day12_dfs::Day12Dfs;
+use crate::day13::Day13;
+use crate::day14::Day14;
+use crate::day15::Day15;
+use crate::day16::Day16;
+use crate::day16_alt::Day16Alt;
+use crate::day17::Day17;
+use crate::day18::Day18;
+use crate::day19::Day19;
+use crate::day20::Day20;
+use crate::day21::Day21;
+use crate::day22::Day22;
+use crate::day23::Day23;
+use crate::day24::Day24;
+use crate::day25::Day25;
+
+/// One runnable implementation of a day's puzzle, e.g. `day12` or `day12_dfs`.
+#[derive(Copy, Clone)]
+pub struct Variant {
+    pub day: usize,
+    pub name: &'static str,
+    run: fn(&str, usize) -> anyhow::Result<(String, &'static str)>,
+    time: fn(&str) -> anyhow::Result<Sample>
+}
+
+impl Variant {
+    pub fn run(&self, filename: &str, part: usize) -> anyhow::Result<String> {
+        self.answer(filename, part).map(|a| a.answer)
+    }
+
+    /// Parses `filename` and solves `part`, timing both together.
+    pub fn answer(&self, filename: &str, part: usize) -> anyhow::Result<Answer> {
+        let start = Instant::now();
+        let (answer, answer_type) = (self.run)(filename, part)?;
+        Ok(Answer {
+            day: self.day, part, variant: self.name, input: filename.to_string(),
+            answer, answer_type, elapsed_us: start.elapsed().as_micros()
+        })
+    }
+
+    pub fn time(&self, filename: &str) -> anyhow::Result<Sample> {
+        (self.time)(filename)
+    }
+
+    pub fn is_default(&self) -> bool {
+        self.name == default_name(self.day)
+    }
+
+    /// `in/dayN.txt` when present, otherwise the first example in `ex/`.
+    pub fn default_input(&self, example: bool) -> anyhow::Result<String> {
+        let puzzle = format!("in/day{}.txt", self.day);
+        if example || !Path::new(puzzle.as_str()).exists() {
+            match day_inputs("ex", self.day)?.into_iter().next() {
+                Some(input) => Ok(input),
+                None => bail!("no example input for day {}", self.day)
+            }
+        } else {
+            Ok(puzzle)
+        }
+    }
+}
+
+/// The answer is kept as a string so that large values (e.g. `BigUint`) survive a JSON round trip.
+#[derive(Clone, Debug, Serialize)]
+pub struct Answer {
+    pub day: usize,
+    pub part: usize,
+    pub variant: &'static str,
+    pub input: String,
+    pub answer: String,
+    pub answer_type: &'static str,
+    pub elapsed_us: u128
+}
+
+macro_rules! variant {
+    ($day:expr, $name:expr, $solver:ty) => {
+        Variant {day: $day, name: $name, run: run_part::<$solver>, time: time_parts::<$solver>}
+    };
+}
+
+pub const VARIANTS: [Variant; 27] = [
+    variant!(1, "day1", Day1),
+    variant!(2, "day2", Day2),
+    variant!(3, "day3", Day3),
+    variant!(4, "day4", Day4),
+    variant!(5, "day5", Day5),
+    variant!(6, "day6", Day6),
+    variant!(7, "day7", Day7),
+    variant!(8, "day8", Day8),
+    variant!(9, "day9", Day9),
+    variant!(10, "day10", Day10),
+    variant!(11, "day11", Day11),
+    variant!(12, "day12", Day12),
+    variant!(12, "day12_dfs", Day12Dfs),
+    variant!(13, "day13", Day13),
+    variant!(14, "day14", Day14),
+    variant!(15, "day15", Day15),
+    variant!(16, "day16", Day16),
+    variant!(16, "day16_alt", Day16Alt),
+    variant!(17, "day17", Day17),
+    variant!(18, "day18", Day18),
+    variant!(19, "day19", Day19),
+    variant!(20, "day20", Day20),
+    variant!(21, "day21", Day21),
+    variant!(22, "day22", Day22),
+    variant!(23, "day23", Day23),
+    variant!(24, "day24", Day24),
+    variant!(25, "day25", Day25),
+];
+
+/// Every `dir/dayN*.txt`, sorted, e.g. `ex/day12a.txt` but not `ex/day1.txt`.
+pub fn day_inputs(dir: &str, day: usize) -> io::Result<Vec<String>> {
+    let prefix = format!("day{}", day);
+    let mut inputs = Vec::new();
+    for entry in fs::read_dir(dir)? {
+        let name = entry?.file_name().to_string_lossy().to_string();
+        if name.strip_prefix(prefix.as_str()).map_or(false, |rest| !rest.starts_with(|c: char| c.is_ascii_digit())) {
+            inputs.push(format!("{}/{}", dir, name));
+        }
+    }
+    inputs.sort();
+    Ok(inputs)
+}
+
+fn default_name(day: usize) -> String {
+    format!("day{}", day)
+}
+
+fn run_part<S: Solver>(filename: &str, part: usize) -> anyhow::Result<(String, &'static str)> {
+    let input = S::parse(filename)?;
+    match part {
+        1 => Ok((S::part1(&input)?.to_string(), short_type_name::<S::Part1>())),
+        2 => Ok((S::part2(&input)?.to_string(), short_type_name::<S::Part2>())),
+        other => bail!("There is no part {}", other)
+    }
+}
+
+fn short_type_name<T>() -> &'static str {
+    type_name::<T>().rsplit("::").next().unwrap()
+}
+
+/// Finds `name` if given, otherwise the default variant for `day`.
+pub fn find_variant(day: usize, name: Option<&str>) -> anyhow::Result<Variant> {
+    let name = name.map_or(default_name(day), |n| n.to_string());
+    VARIANTS.iter()
+        .find(|v| v.day == day && v.name == name.as_str())
+        .copied()
+        .ok_or(anyhow!("No variant {} for day {}", name, day))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_short_type_name() {
+        assert_eq!(short_type_name::<usize>(), "usize");
+        assert_eq!(short_type_name::<String>(), "String");
+        assert_eq!(short_type_name::<num::BigUint>(), "BigUint");
+    }
+
+    #[test]
+    fn test_example_input() {
+        assert_eq!(find_variant(1, None).unwrap().default_input(true).unwrap(), "ex/day1.txt");
+        assert_eq!(find_variant(12, None).unwrap().default_input(true).unwrap(), "ex/day12a.txt");
+        assert_eq!(find_variant(18, None).unwrap().default_input(true).unwrap(), "ex/day18_1.txt");
+        assert_eq!(find_variant(16, None).unwrap().default_input(true).unwrap_err().to_string(), "no example input for day 16");
+    }
+}
@@ -0,0 +1,36 @@
+use std::process::Command;
+use advent2021::answers::{Answers, Expected, ANSWERS_FILE};
+use advent2021::runner::VARIANTS;
+
+/// Solvers must not print to stdout, or `--format json` stops being one record per line.
+/// Only parts with a recorded answer for the day's example are run, since the others have nothing to print.
+#[test]
+fn test_json_lines_parse() {
+    let answers = Answers::from_file(ANSWERS_FILE).unwrap();
+    for variant in VARIANTS.iter().filter(|v| v.is_default()) {
+        let input = match variant.default_input(true) {
+            Ok(input) => input,
+            Err(_) => continue
+        };
+        for part in [1, 2] {
+            if !matches!(answers.expected(variant.day, input.as_str(), part), Some(Expected::Answer(_))) {
+                continue;
+            }
+            let output = Command::new(env!("CARGO_BIN_EXE_aoc"))
+                .args(["--day", variant.day.to_string().as_str(), "--part", part.to_string().as_str(),
+                    "--input", input.as_str(), "--format", "json"])
+                .output()
+                .unwrap();
+            assert!(output.status.success(), "{} part {} on {} failed", variant.name, part, input);
+            let lines = String::from_utf8(output.stdout).unwrap().lines().map(|line| line.to_string()).collect::<Vec<_>>();
+            assert!(!lines.is_empty(), "{} part {}: no records", variant.name, part);
+            for line in lines {
+                let record = serde_json::from_str::<serde_json::Value>(line.as_str());
+                assert!(record.is_ok(), "{}: not JSON: {}", variant.name, line);
+                let record = record.unwrap();
+                assert_eq!(record["day"], variant.day);
+                assert_eq!(record["part"], part);
+            }
+        }
+    }
+}